@@ -1,4 +1,7 @@
 use crate::commands::types::*;
+use crate::injection::{copy_tree, detect_champion, extract_zip, find_mods, global_index_for, hash_file, hash_table, is_mod_folder, mod_name, validate_mod, write_preview, zip_contains_mods, ModKind};
+use std::collections::HashMap;
+use tauri::Emitter;
 use tauri::Manager;
 use chrono;
 use serde_json;
@@ -6,14 +9,15 @@ use serde_json;
 use std::os::windows::process::CommandExt;
 
 // TODO: Move these functions from commands.rs:
+// champion_id is optional, without it the champion is detected from the skin's contents
 #[tauri::command]
 pub async fn upload_custom_skin(
     app: tauri::AppHandle,
-    champion_id: u32,
+    champion_id: Option<u32>,
     skin_name: String,
 ) -> Result<CustomSkinData, String> {
     println!("Uploading custom skin: {}", skin_name);
-    println!("For champion ID: {}", champion_id);
+    println!("For champion ID: {:?}", champion_id);
     
     // Open file dialog for the user to select a skin file
     #[cfg(target_os = "windows")]
@@ -60,6 +64,185 @@ pub async fn upload_custom_skin(
     
     println!("Selected file: {}", file_path);
     
+    let mut custom_skins = get_custom_skins(app.clone()).await.unwrap_or_default();
+    let mut known = backfill_custom_skins(&app, &mut custom_skins);
+    let source_path = std::path::Path::new(&file_path);
+    match import_custom_skin(&app, source_path, champion_id, Some(skin_name), &mut known).await? {
+        ImportedSkin::Existing(existing) => {
+            println!("Skin file was already imported as {}", existing.name);
+            Ok(existing)
+        }
+        ImportedSkin::New(custom_skin) => {
+            // Save metadata about this custom skin
+            custom_skins.push(custom_skin.clone());
+            write_custom_skins(&app, &custom_skins)?;
+            Ok(custom_skin)
+        }
+    }
+}
+
+// Import every mod in a folder, or in a zip of mods, as custom skins.
+// Champions are detected from each mod's contents and files already imported are skipped.
+#[tauri::command]
+pub async fn import_custom_skins(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<CustomSkinImportReport, String> {
+    println!("Importing custom skins from: {}", path);
+    let source = std::path::Path::new(&path);
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    // A zip of mods is unpacked first, a single mod file is imported as-is
+    let is_mod_pack = || -> Result<bool, String> {
+        if ModKind::detect(source) != Some(ModKind::Zip) {
+            return Ok(false);
+        }
+        let report = validate_mod(source).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if report.valid {
+            return Ok(false);
+        }
+        // Not a mod and no mods inside either, the validation errors say why
+        match zip_contains_mods(source) {
+            Ok(true) => Ok(true),
+            _ => Err(format!("Invalid skin file: {}", report.errors.join("; "))),
+        }
+    };
+    let mut extracted_dir = None;
+    let mods = if is_mod_folder(source) {
+        vec![source.to_path_buf()]
+    } else if source.is_dir() {
+        find_mods(source).map_err(|e| format!("Failed to scan {}: {}", path, e))?
+    } else if is_mod_pack()? {
+        let temp_dir = app_data_dir.join("temp").join(format!("import_{}", chrono::Utc::now().timestamp_millis()));
+        let scanned = extract_zip(source, &temp_dir)
+            .and_then(|_| find_mods(&temp_dir))
+            .map_err(|e| format!("Failed to unpack {}: {}", path, e));
+        extracted_dir = Some(temp_dir);
+        match scanned {
+            Ok(mods) => mods,
+            Err(e) => {
+                if let Some(dir) = &extracted_dir {
+                    let _ = std::fs::remove_dir_all(dir);
+                }
+                return Err(e);
+            }
+        }
+    } else if ModKind::detect(source).is_some() {
+        vec![source.to_path_buf()]
+    } else {
+        return Err(format!("Nothing to import at {}", path));
+    };
+    println!("Found {} mods to import", mods.len());
+
+    let mut custom_skins = get_custom_skins(app.clone()).await.unwrap_or_default();
+    let mut known = backfill_custom_skins(&app, &mut custom_skins);
+    let mut results = Vec::with_capacity(mods.len());
+    for (index, mod_path) in mods.iter().enumerate() {
+        let _ = app.emit("custom-skin-import-progress", serde_json::json!({
+            "current": index + 1,
+            "total": mods.len(),
+            "file": mod_name(mod_path),
+        }));
+        let source = extracted_dir.as_ref()
+            .and_then(|dir| mod_path.strip_prefix(dir).ok())
+            .map(|rel| format!("{}/{}", path, rel.display()))
+            .unwrap_or_else(|| mod_path.display().to_string());
+
+        let result = match import_custom_skin(&app, mod_path, None, None, &mut known).await {
+            Ok(ImportedSkin::New(skin)) => {
+                custom_skins.push(skin.clone());
+                CustomSkinImportResult { source, status: CustomSkinImportStatus::Imported, skin: Some(skin), error: None }
+            }
+            Ok(ImportedSkin::Existing(skin)) => {
+                CustomSkinImportResult { source, status: CustomSkinImportStatus::Duplicate, skin: Some(skin), error: None }
+            }
+            Err(e) => {
+                println!("Failed to import {}: {}", source, e);
+                CustomSkinImportResult { source, status: CustomSkinImportStatus::Failed, skin: None, error: Some(e) }
+            }
+        };
+        results.push(result);
+    }
+
+    if let Some(dir) = &extracted_dir {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    write_custom_skins(&app, &custom_skins)?;
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let report = CustomSkinImportReport {
+        imported: count(CustomSkinImportStatus::Imported),
+        duplicates: count(CustomSkinImportStatus::Duplicate),
+        failed: count(CustomSkinImportStatus::Failed),
+        results,
+    };
+    println!("Imported {} custom skins ({} duplicates, {} failed)", report.imported, report.duplicates, report.failed);
+    Ok(report)
+}
+
+enum ImportedSkin {
+    New(CustomSkinData),
+    Existing(CustomSkinData), // Same content was imported before
+}
+
+// Content hash -> custom skin, filling in hashes and previews missing from older entries.
+// Whatever was filled in is saved right away, so it is only worked out once.
+fn backfill_custom_skins(app: &tauri::AppHandle, custom_skins: &mut [CustomSkinData]) -> HashMap<String, CustomSkinData> {
+    let mut known = HashMap::new();
+    let mut changed = false;
+    for skin in custom_skins.iter_mut() {
+        let file_path = std::path::Path::new(&skin.file_path);
+        if (skin.content_hash.is_none() || skin.preview_image.is_none()) && file_path.exists() {
+            if skin.content_hash.is_none() {
+                skin.content_hash = hash_file(file_path).ok();
+                changed |= skin.content_hash.is_some();
+            }
+            if skin.preview_image.is_none() {
+                skin.preview_image = create_preview(app, file_path, skin).ok();
+                changed |= skin.preview_image.is_some();
+            }
+        }
+        if let Some(hash) = &skin.content_hash {
+            known.insert(hash.clone(), skin.clone());
+        }
+    }
+    if changed {
+        if let Err(e) = write_custom_skins(app, custom_skins) {
+            println!("Failed to save backfilled custom skins: {}", e);
+        }
+    }
+    known
+}
+
+// Validate one mod file or folder, work out its champion and copy it into custom_skins/
+async fn import_custom_skin(
+    app: &tauri::AppHandle,
+    source_path: &std::path::Path,
+    champion_id: Option<u32>,
+    skin_name: Option<String>,
+    known: &mut HashMap<String, CustomSkinData>,
+) -> Result<ImportedSkin, String> {
+    // Reject broken or malicious files before they are stored
+    // (.fantome, .zip and bare WADs are all accepted and normalized at injection time)
+    let report = validate_mod(source_path)
+        .map_err(|e| format!("Failed to read skin file: {}", e))?;
+    for warning in &report.warnings {
+        println!("Custom skin warning: {}", warning);
+    }
+    if !report.valid {
+        return Err(format!("Invalid skin file: {}", report.errors.join("; ")));
+    }
+
+    let content_hash = hash_file(source_path)
+        .map_err(|e| format!("Failed to read skin file: {}", e))?;
+    if let Some(existing) = known.get(&content_hash) {
+        return Ok(ImportedSkin::Existing(existing.clone()));
+    }
+    let skin_name = skin_name
+        .or_else(|| report.info.as_ref().map(|info| info.name.clone()).filter(|name| !name.trim().is_empty()))
+        .unwrap_or_else(|| mod_name(source_path));
+    
     // Get the app data directory
     let app_data_dir = app.path().app_data_dir()
         .or_else(|e| Err(format!("Failed to get app data directory: {}", e)))?;
@@ -69,11 +252,15 @@ pub async fn upload_custom_skin(
     std::fs::create_dir_all(&custom_skins_dir)
         .map_err(|e| format!("Failed to create custom skins directory: {}", e))?;
         
-    // Get champion name (for organization)
-    let champion_name = if let Ok(champion_data) = get_champion_name(&app, champion_id).await {
-        champion_data
-    } else {
-        format!("champion_{}", champion_id) // Fallback if name not found
+    // Get champion ID and name (for organization)
+    let (champion_id, champion_name) = match champion_id {
+        Some(champion_id) => (champion_id, get_champion_name(app, champion_id).await?),
+        None => {
+            let hashes = hash_table(&app_data_dir.join("hashes"));
+            detect_champion(source_path, &app_data_dir.join("champions"), hashes.as_deref())
+                .map_err(|e| format!("Failed to read skin file: {}", e))?
+                .ok_or_else(|| "Could not detect the champion of this skin, please pick it manually".to_string())?
+        }
     };
     
     // Create directory for this champion's custom skins
@@ -82,43 +269,51 @@ pub async fn upload_custom_skin(
         .map_err(|e| format!("Failed to create champion directory: {}", e))?;
         
     // Generate a unique ID for this skin
-    let skin_id = format!("custom_{}_{}", champion_id, chrono::Utc::now().timestamp());
-    
-    // Copy the file to the custom skins directory with a new name
-    let source_path = std::path::Path::new(&file_path);
-    let file_ext = source_path.extension()
-        .map(|ext| ext.to_string_lossy().to_string())
-        .unwrap_or_else(|| "fantome".to_string());
-    
-    // Create filename: champion_name_skinid.extension
-    let dest_filename = format!("{}_{}.{}", champion_name, skin_id, file_ext);
-    let dest_path = champion_dir.join(&dest_filename);
+    let skin_id = format!("custom_{}_{}_{}", champion_id, chrono::Utc::now().timestamp(), &content_hash[..8]);
     
-    // Copy the file
-    std::fs::copy(source_path, &dest_path)
-        .map_err(|e| format!("Failed to copy skin file: {}", e))?;
+    // Copy the file (or mod folder) to the custom skins directory with a new name:
+    // champion_name_skinid.extension
+    let dest_path = if source_path.is_dir() {
+        let dest_path = champion_dir.join(format!("{}_{}", champion_name, skin_id));
+        copy_tree(source_path, &dest_path, &|| Ok(()))
+            .map_err(|e| format!("Failed to copy skin folder: {}", e))?;
+        dest_path
+    } else {
+        let file_ext = source_path.extension()
+            .map(|ext| ext.to_string_lossy().to_string())
+            .unwrap_or_else(|| "fantome".to_string());
+        let dest_path = champion_dir.join(format!("{}_{}.{}", champion_name, skin_id, file_ext));
+        std::fs::copy(source_path, &dest_path)
+            .map_err(|e| format!("Failed to copy skin file: {}", e))?;
+        dest_path
+    };
         
     // Create metadata for the custom skin
-    let custom_skin = CustomSkinData {
+    let mut custom_skin = CustomSkinData {
         id: skin_id,
         name: skin_name,
         champion_id,
         champion_name,
         file_path: dest_path.to_string_lossy().to_string(),
         created_at: chrono::Utc::now().timestamp() as u64,
-        preview_image: None, // We'll leave preview generation for a future enhancement
+        preview_image: None,
+        content_hash: Some(content_hash.clone()),
     };
-    
-    // Save metadata about this custom skin
-    save_custom_skin(&app, &custom_skin).await?;
-    
-    Ok(custom_skin)
+    custom_skin.preview_image = create_preview(app, &dest_path, &custom_skin)
+        .map_err(|e| println!("Failed to create preview for {}: {}", custom_skin.name, e))
+        .ok();
+    known.insert(content_hash, custom_skin.clone());
+    Ok(ImportedSkin::New(custom_skin))
 }
 
 #[tauri::command]
 pub async fn get_custom_skins(
     app: tauri::AppHandle
 ) -> Result<Vec<CustomSkinData>, String> {
+    read_custom_skins(&app)
+}
+
+pub fn read_custom_skins(app: &tauri::AppHandle) -> Result<Vec<CustomSkinData>, String> {
     let config_dir = app.path().app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?
         .join("config");
@@ -152,79 +347,53 @@ pub async fn delete_custom_skin(
     
     // Delete the skin file
     let file_path = std::path::Path::new(&skin_to_delete.file_path);
-    if file_path.exists() {
+    if file_path.is_dir() {
+        std::fs::remove_dir_all(file_path)
+            .map_err(|e| format!("Failed to delete skin folder: {}", e))?;
+    } else if file_path.exists() {
         std::fs::remove_file(file_path)
             .map_err(|e| format!("Failed to delete skin file: {}", e))?;
     }
     
+    if let Some(preview) = &skin_to_delete.preview_image {
+        let _ = std::fs::remove_file(preview);
+    }
+    
     // Update the custom skins list
     let updated_skins: Vec<CustomSkinData> = custom_skins.into_iter()
         .filter(|skin| skin.id != skin_id)
         .collect();
         
     // Save the updated list
-    let config_dir = app.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data dir: {}", e))?
-        .join("config");
-    std::fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Failed to create config dir: {}", e))?;
-        
-    let file = config_dir.join("custom_skins.json");
-    let data = serde_json::to_string_pretty(&updated_skins)
-        .map_err(|e| format!("Failed to serialize custom skins: {}", e))?;
-        
-    std::fs::write(&file, data)
-        .map_err(|e| format!("Failed to write custom_skins.json: {}", e))?;
-        
-    Ok(())
+    write_custom_skins(&app, &updated_skins)
 }
 
 async fn get_champion_name(app: &tauri::AppHandle, champion_id: u32) -> Result<String, String> {
     let app_data_dir = app.path().app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?;
         
-    let champions_dir = app_data_dir.join("champions");
-    
-    // Look through champion directories to find the one with matching ID
-    if champions_dir.exists() {
-        for entry in std::fs::read_dir(champions_dir).map_err(|e| e.to_string())? {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    let json_file = path.join(format!("{}.json", entry.file_name().to_string_lossy()));
-                    
-                    if json_file.exists() {
-                        if let Ok(content) = std::fs::read_to_string(&json_file) {
-                            if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                                if let Some(id) = data.get("id").and_then(|v| v.as_u64()) {
-                                    if id as u32 == champion_id {
-                                        if let Some(name) = data.get("name").and_then(|v| v.as_str()) {
-                                            let _name = name;
-                                            // Use champion directory name instead of display name for consistency
-                                            return Ok(entry.file_name().to_string_lossy().to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    // Use champion directory name instead of display name for consistency
+    let index = global_index_for(&app_data_dir.join("champions"))
+        .map_err(|e| format!("Failed to load champion index: {}", e))?;
+    let name = index.lock().unwrap().get_champion_name(champion_id);
     
     // Fallback
-    Ok(format!("champion_{}", champion_id))
+    Ok(name.unwrap_or_else(|| format!("champion_{}", champion_id)))
 }
 
-async fn save_custom_skin(app: &tauri::AppHandle, custom_skin: &CustomSkinData) -> Result<(), String> {
-    // Get all existing custom skins
-    let mut custom_skins = get_custom_skins(app.clone()).await.unwrap_or_default();
-    
-    // Add the new skin
-    custom_skins.push(custom_skin.clone());
-    
-    // Save to file
+// Thumbnail from the mod's META/image.png, or a generated placeholder
+fn create_preview(app: &tauri::AppHandle, file_path: &std::path::Path, skin: &CustomSkinData) -> Result<String, String> {
+    let thumbnails_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("thumbnails")
+        .join("custom");
+    let seed = skin.content_hash.as_deref().unwrap_or(&skin.id);
+    let preview = write_preview(file_path, &thumbnails_dir.join(&skin.id), &skin.name, &skin.champion_name, seed)
+        .map_err(|e| format!("Failed to write preview image: {}", e))?;
+    Ok(preview.to_string_lossy().to_string())
+}
+
+fn write_custom_skins(app: &tauri::AppHandle, custom_skins: &[CustomSkinData]) -> Result<(), String> {
     let config_dir = app.path().app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?
         .join("config");
@@ -232,11 +401,11 @@ async fn save_custom_skin(app: &tauri::AppHandle, custom_skin: &CustomSkinData)
         .map_err(|e| format!("Failed to create config dir: {}", e))?;
         
     let file = config_dir.join("custom_skins.json");
-    let data = serde_json::to_string_pretty(&custom_skins)
+    let data = serde_json::to_string_pretty(custom_skins)
         .map_err(|e| format!("Failed to serialize custom skins: {}", e))?;
         
     std::fs::write(&file, data)
         .map_err(|e| format!("Failed to write custom_skins.json: {}", e))?;
     
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 use crate::commands::types::*;
+use crate::injection::{hash_table, is_hash_file, reload_hash_table};
 use tauri::{AppHandle, Manager};
 use std::path::PathBuf;
 use std::fs;
+use std::io::Write;
 use reqwest;
 use serde_json;
 use chrono;
@@ -86,9 +88,9 @@ pub async fn check_champions_data(app: tauri::AppHandle) -> Result<bool, String>
         .any(|champion_dir| {
             fs::read_dir(champion_dir.path())
                 .ok()
-                .map_or(false, |mut entries| {
+                .is_some_and(|mut entries| {
                     entries.any(|entry| {
-                        entry.map_or(false, |e| {
+                        entry.is_ok_and(|e| {
                             e.path().extension().and_then(|s| s.to_str()) == Some("json")
                         })
                     })
@@ -555,4 +557,153 @@ fn load_data_version(app: &AppHandle) -> Result<Option<DataVersion>, String> {
         .map_err(|e| format!("Failed to parse data version: {}", e))?;
     
     Ok(Some(version))
-}
\ No newline at end of file
+}
+
+// Download the WAD path hash dictionaries, or only the parts that changed since the last update.
+// Lookups read the local files, so inspecting mods works offline once they are present.
+#[tauri::command]
+pub async fn update_wad_hashes(app: tauri::AppHandle) -> Result<HashesUpdateResult, String> {
+    println!("Checking WAD hash dictionaries for updates...");
+
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let hashes_dir = app_data_dir.join("hashes");
+    fs::create_dir_all(&hashes_dir)
+        .map_err(|e| format!("Failed to create hashes directory: {}", e))?;
+
+    let client = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client.get(HASHES_API_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+        .send()
+        .await
+        .map_err(|e| format!("Network error connecting to GitHub: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub API returned error: {}", response.status()));
+    }
+    let listing: Vec<GitHubContentEntry> = response.json()
+        .await
+        .map_err(|e| format!("Failed to parse hash dictionary listing: {}", e))?;
+    let remote: Vec<GitHubContentEntry> = listing.into_iter()
+        .filter(|entry| is_hash_file(&entry.name) && entry.download_url.is_some())
+        .collect();
+    if remote.is_empty() {
+        return Err("No hash dictionary files found on GitHub".to_string());
+    }
+
+    let mut version = load_hashes_version(&app)?;
+    let mut updated_files = Vec::new();
+    for entry in &remote {
+        let local = hashes_dir.join(&entry.name);
+        if version.files.get(&entry.name) == Some(&entry.sha) && local.exists() {
+            continue;
+        }
+        println!("Downloading {} ({} bytes)", entry.name, entry.size);
+        download_to_file(&client, entry.download_url.as_deref().unwrap_or_default(), &local).await?;
+        version.files.insert(entry.name.clone(), entry.sha.clone());
+        updated_files.push(entry.name.clone());
+    }
+
+    // Parts that were merged or dropped upstream
+    let mut removed_files = Vec::new();
+    if let Ok(entries) = fs::read_dir(&hashes_dir) {
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_hash_file(&name) && !remote.iter().any(|r| r.name == name) {
+                let _ = fs::remove_file(entry.path());
+                version.files.remove(&name);
+                removed_files.push(name);
+            }
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    version.last_checked = now;
+    if !updated_files.is_empty() || !removed_files.is_empty() {
+        version.last_updated = now;
+    }
+    save_hashes_version(&app, &version)?;
+
+    // Reading millions of lines takes a while, keep it off the async runtime
+    let changed = !updated_files.is_empty() || !removed_files.is_empty();
+    let table = tauri::async_runtime::spawn_blocking(move || {
+        if changed {
+            reload_hash_table(&hashes_dir)
+        } else {
+            Ok(hash_table(&hashes_dir))
+        }
+    })
+        .await
+        .map_err(|e| format!("Failed to load hash dictionary: {}", e))?
+        .map_err(|e| format!("Failed to load hash dictionary: {}", e))?;
+    let entry_count = table.map(|t| t.len()).unwrap_or(0);
+
+    Ok(HashesUpdateResult {
+        success: true,
+        update_message: Some(if updated_files.is_empty() {
+            format!("Hash dictionary is up to date ({} hashes)", entry_count)
+        } else {
+            format!("Updated {} hash dictionary files ({} hashes)", updated_files.len(), entry_count)
+        }),
+        updated_files,
+        removed_files,
+        entry_count,
+    })
+}
+
+// Stream a download into place; the old file stays until the new one is complete
+async fn download_to_file(client: &reqwest::Client, url: &str, target: &std::path::Path) -> Result<(), String> {
+    let mut response = client.get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download {}: {}", url, response.status()));
+    }
+
+    let file_name = target.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let tmp = target.with_file_name(format!("{}.tmp", file_name));
+    let mut file = fs::File::create(&tmp)
+        .map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+    while let Some(chunk) = response.chunk()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    }
+    drop(file);
+    fs::rename(&tmp, target)
+        .map_err(|e| format!("Failed to replace {}: {}", target.display(), e))?;
+    Ok(())
+}
+
+fn get_hashes_version_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    let config_dir = app_data_dir.join("config");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config dir: {}", e))?;
+    Ok(config_dir.join("hashes_version.json"))
+}
+
+fn load_hashes_version(app: &AppHandle) -> Result<HashesVersion, String> {
+    let file_path = get_hashes_version_path(app)?;
+    Ok(std::fs::read_to_string(&file_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default())
+}
+
+fn save_hashes_version(app: &AppHandle, version: &HashesVersion) -> Result<(), String> {
+    let file_path = get_hashes_version_path(app)?;
+    let data = serde_json::to_string_pretty(version)
+        .map_err(|e| format!("Failed to serialize hashes version: {}", e))?;
+    std::fs::write(&file_path, data)
+        .map_err(|e| format!("Failed to write hashes version file: {}", e))?;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::commands::types::*;
+use crate::injection::InjectionTimeouts;
 use tauri::{Manager};
 use std::fs;
 use serde_json;
@@ -123,7 +124,9 @@ pub async fn save_selected_skins(
     league_path: String, 
     skins: Vec<SkinData>, 
     favorites: Vec<u32>,
-    theme: Option<ThemePreferences>
+    theme: Option<ThemePreferences>,
+    mod_priority: Option<Vec<String>>,
+    injection_timeouts: Option<InjectionTimeouts>
 ) -> Result<(), String> {
     let config_dir = app.path().app_data_dir()
         .map_err(|e| format!("Failed to get app data dir: {}", e))?
@@ -131,12 +134,29 @@ pub async fn save_selected_skins(
     std::fs::create_dir_all(&config_dir)
         .map_err(|e| format!("Failed to create config dir: {}", e))?;
     let file = config_dir.join("config.json");
+    // Keep settings the caller doesn't send (priority order, timeouts)
+    let existing = std::fs::read_to_string(&file)
+        .ok()
+        .and_then(|content| serde_json::from_str::<SavedConfig>(&content).ok());
+    if let Some(timeouts) = &injection_timeouts {
+        if timeouts.extraction_secs == 0 || timeouts.overlay_build_secs == 0 || timeouts.patcher_start_secs == 0 {
+            return Err("Injection timeouts must be at least one second".to_string());
+        }
+    }
+    let injection_timeouts = injection_timeouts.unwrap_or_else(|| {
+        existing.as_ref().map(|cfg| cfg.injection_timeouts).unwrap_or_default()
+    });
+    let mod_priority = mod_priority.unwrap_or_else(|| {
+        existing.map(|cfg| cfg.mod_priority).unwrap_or_default()
+    });
     // build combined JSON
     let config_json = serde_json::json!({
         "league_path": league_path,
         "skins": skins,
         "favorites": favorites,
-        "theme": theme
+        "theme": theme,
+        "mod_priority": mod_priority,
+        "injection_timeouts": injection_timeouts
     });
     let data = serde_json::to_string_pretty(&config_json)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
@@ -153,7 +173,7 @@ pub async fn load_config(app: tauri::AppHandle) -> Result<SavedConfig, String> {
         .join("config");
     let file = config_dir.join("config.json");
     if !file.exists() {
-        return Ok(SavedConfig { league_path: None, skins: Vec::new(), favorites: Vec::new(), theme: None });
+        return Ok(SavedConfig { league_path: None, skins: Vec::new(), favorites: Vec::new(), theme: None, mod_priority: Vec::new(), injection_timeouts: Default::default() });
     }
     let content = std::fs::read_to_string(&file)
         .map_err(|e| format!("Failed to read config.json: {}", e))?;
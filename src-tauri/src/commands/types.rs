@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use crate::injection::Skin;
+use crate::injection::{InjectionTimeouts, Skin};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DataUpdateProgress {
@@ -22,6 +23,8 @@ pub struct SkinData {
     pub skin_id: u32,
     pub chroma_id: Option<u32>,
     pub fantome: Option<String>,
+    #[serde(default)]
+    pub custom_skin_id: Option<String>, // CustomSkinData id when a custom skin is selected
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +36,33 @@ pub struct CustomSkinData {
     pub file_path: String,
     pub created_at: u64,
     pub preview_image: Option<String>,
+    #[serde(default)]
+    pub content_hash: Option<String>, // md5 of the stored file, used to skip duplicate imports
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomSkinImportStatus {
+    Imported,
+    Duplicate, // Same content as a custom skin that is already stored
+    Failed,
+}
+
+// Outcome for one mod of a bulk import
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomSkinImportResult {
+    pub source: String,
+    pub status: CustomSkinImportStatus,
+    pub skin: Option<CustomSkinData>, // The new skin, or the existing one for duplicates
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomSkinImportReport {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+    pub results: Vec<CustomSkinImportResult>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -96,6 +126,32 @@ pub struct DataUpdateResult {
     pub changelog: Option<String>,
 }
 
+// A file listed by the GitHub contents API
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubContentEntry {
+    pub name: String,
+    pub sha: String, // Git blob sha, changes whenever the file does
+    pub size: u64,
+    pub download_url: Option<String>,
+}
+
+// Blob sha of every downloaded WAD hash dictionary file, so only changed parts are fetched again
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HashesVersion {
+    pub files: HashMap<String, String>,
+    pub last_checked: i64,
+    pub last_updated: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HashesUpdateResult {
+    pub success: bool,
+    pub updated_files: Vec<String>,
+    pub removed_files: Vec<String>,
+    pub entry_count: usize,
+    pub update_message: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ThemePreferences {
     pub tone: Option<String>,
@@ -110,6 +166,11 @@ pub struct SavedConfig {
     pub favorites: Vec<u32>,
     #[serde(default)]
     pub theme: Option<ThemePreferences>,
+    // Mod names in priority order, the first one wins when mods override the same files
+    #[serde(default)]
+    pub mod_priority: Vec<String>,
+    #[serde(default)]
+    pub injection_timeouts: InjectionTimeouts,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -119,21 +180,12 @@ pub struct TerminalLog {
     pub timestamp: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Friend {
-    pub id: String,
-    pub name: String,
-    pub availability: String,
-    #[serde(rename = "gameTag")]
-    pub game_tag: Option<String>,
-    #[serde(rename = "note")]
-    pub note: Option<String>,
-}
-
 // Constants
 pub const GITHUB_API_URL: &str = "https://api.github.com/repos/darkseal-org/lol-skins-developer";
 pub const USER_AGENT: &str = "fuck-exalted-app/1.0";
 pub const _DATA_VERSION_FILE: &str = "data_version.json";
 pub const GITHUB_API_VERSION: &str = "2022-11-28";
 pub const _GITHUB_REPO_OWNER: &str = "darkseal-org";
-pub const _GITHUB_REPO_NAME: &str = "lol-skins-developer";
\ No newline at end of file
+pub const _GITHUB_REPO_NAME: &str = "lol-skins-developer";
+// CommunityDragon's WAD path hash dictionaries (hashes.game.txt, split in parts)
+pub const HASHES_API_URL: &str = "https://api.github.com/repos/CommunityDragon/Data/contents/hashes/lol";
\ No newline at end of file
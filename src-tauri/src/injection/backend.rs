@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::injection::error::InjectionError;
+use crate::injection::patcher::CommandFactory;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+// Everything the injector needs from the external mod-tools binary
+// The overlay itself is built natively; mod-tools is only used to patch the running game.
+pub trait ModToolsBackend: Send + Sync {
+    // Human readable description for logs, e.g. the mod-tools.exe path
+    fn describe(&self) -> String;
+
+    // Fail early if the patcher can't be run at all
+    fn check_available(&self) -> Result<(), InjectionError>;
+
+    // Command that runs `runoverlay` for the given overlay, rebuilt on every restart
+    fn runoverlay_command(
+        &self,
+        overlay_dir: &Path,
+        config_path: &Path,
+        game_path: &Path,
+    ) -> Result<CommandFactory, InjectionError>;
+
+    // Kill patcher processes we don't own (e.g. left over from a previous app run)
+    fn kill_stray_processes(&self);
+}
+
+// Real backend: cslol's mod-tools.exe
+pub struct ModToolsExe {
+    path: Option<PathBuf>,
+}
+
+impl ModToolsExe {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+
+    fn require_path(&self) -> Result<PathBuf, InjectionError> {
+        match &self.path {
+            Some(path) => {
+                if !path.exists() {
+                    return Err(InjectionError::OverlayError(format!(
+                        "mod-tools.exe was found during initialization but is no longer at path: {}. Please reinstall the application or obtain mod-tools.exe from CSLOL Manager.",
+                        path.display()
+                    )));
+                }
+                Ok(path.clone())
+            },
+            None => Err(InjectionError::OverlayError(
+                "mod-tools.exe not found. Please install CSLOL Manager or copy mod-tools.exe to the application directory.".into()
+            )),
+        }
+    }
+}
+
+impl ModToolsBackend for ModToolsExe {
+    fn describe(&self) -> String {
+        match &self.path {
+            Some(path) => format!("mod-tools.exe at {}", path.display()),
+            None => "mod-tools.exe (not found)".to_string(),
+        }
+    }
+
+    fn check_available(&self) -> Result<(), InjectionError> {
+        self.require_path().map(|_| ())
+    }
+
+    fn runoverlay_command(
+        &self,
+        overlay_dir: &Path,
+        config_path: &Path,
+        game_path: &Path,
+    ) -> Result<CommandFactory, InjectionError> {
+        let program = self.require_path()?;
+        // EXACT format from CSLOL
+        let args = vec![
+            "runoverlay".to_string(),
+            overlay_dir.to_string_lossy().to_string(),
+            config_path.to_string_lossy().to_string(),
+            format!("--game:{}", game_path.to_string_lossy()),
+            "--opts:configless".to_string(),
+        ];
+        Ok(Box::new(move || {
+            let mut command = Command::new(&program);
+            command.args(&args);
+            #[cfg(target_os = "windows")]
+            command.creation_flags(CREATE_NO_WINDOW);
+            command
+        }))
+    }
+
+    fn kill_stray_processes(&self) {
+        #[cfg(target_os = "windows")]
+        {
+            // First try normal taskkill
+            let mut command = Command::new("taskkill");
+            command.args(["/F", "/IM", "mod-tools.exe"]);
+            command.creation_flags(CREATE_NO_WINDOW);
+            let _ = command.output();
+
+            // Then check if any processes are still running with wmic (more reliable)
+            let mut check_command = Command::new("wmic");
+            check_command.args(["process", "where", "name='mod-tools.exe'", "get", "processid"]);
+            check_command.creation_flags(CREATE_NO_WINDOW);
+
+            // If we find any processes still running, use taskkill with /PID for each one
+            if let Ok(output) = check_command.output() {
+                if output.status.success() {
+                    let output_str = String::from_utf8_lossy(&output.stdout);
+                    for line in output_str.lines() {
+                        let line = line.trim();
+                        if line != "ProcessId" && !line.is_empty() && line.chars().all(|c| c.is_digit(10)) {
+                            // Found a PID, kill it specifically
+                            let mut kill_pid = Command::new("taskkill");
+                            kill_pid.args(["/F", "/PID", line]);
+                            kill_pid.creation_flags(CREATE_NO_WINDOW);
+                            let _ = kill_pid.output();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Scriptable stand-in for mod-tools, so the injection flow can run on any OS
+#[cfg(feature = "test-support")]
+pub mod fake {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    pub struct FakeModTools {
+        output: Vec<String>,
+        exit_code: Option<i32>,
+        available: bool,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeModTools {
+        // Prints the usual patcher statuses and keeps running until stopped
+        pub fn new() -> Self {
+            Self {
+                output: vec![
+                    "Status: Waiting for league match to start".into(),
+                    "Status: Found League".into(),
+                    "Status: Patching".into(),
+                    "Status: Waiting for exit".into(),
+                ],
+                exit_code: None,
+                available: true,
+                calls: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+
+        pub fn with_output(mut self, lines: &[&str]) -> Self {
+            self.output = lines.iter().map(|line| line.to_string()).collect();
+            self
+        }
+
+        // Exit right after printing the output, e.g. to simulate a crash
+        pub fn exiting_with(mut self, code: i32) -> Self {
+            self.exit_code = Some(code);
+            self
+        }
+
+        pub fn unavailable(mut self) -> Self {
+            self.available = false;
+            self
+        }
+
+        // Every call made by the injector, in order ("runoverlay <dir>", "kill")
+        pub fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn script(&self) -> String {
+            let mut script = String::new();
+            for line in &self.output {
+                script.push_str(&format!("echo '{}'\n", line.replace('\'', "'\\''")));
+            }
+            match self.exit_code {
+                Some(code) => script.push_str(&format!("exit {}\n", code)),
+                // Like runoverlay: exit on a newline from stdin
+                None => script.push_str("read _\n"),
+            }
+            script
+        }
+    }
+
+    impl Default for FakeModTools {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl ModToolsBackend for FakeModTools {
+        fn describe(&self) -> String {
+            "fake mod-tools".to_string()
+        }
+
+        fn check_available(&self) -> Result<(), InjectionError> {
+            if self.available {
+                Ok(())
+            } else {
+                Err(InjectionError::OverlayError("fake mod-tools is unavailable".into()))
+            }
+        }
+
+        fn runoverlay_command(
+            &self,
+            overlay_dir: &Path,
+            _config_path: &Path,
+            _game_path: &Path,
+        ) -> Result<CommandFactory, InjectionError> {
+            self.check_available()?;
+            self.calls.lock().unwrap().push(format!("runoverlay {}", overlay_dir.display()));
+            let script = self.script();
+            Ok(Box::new(move || {
+                let mut command = Command::new("sh");
+                command.arg("-c").arg(&script);
+                command
+            }))
+        }
+
+        fn kill_stray_processes(&self) {
+            self.calls.lock().unwrap().push("kill".to_string());
+        }
+    }
+}
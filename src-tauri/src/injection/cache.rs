@@ -1,5 +1,328 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{Instant, Duration};
-use once_cell::sync::Lazy;
\ No newline at end of file
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::injection::error::InjectionError;
+
+// Content-addressed overlay cache
+// Each built overlay lives in overlay_cache/<key>/ where the key is an md5 of the
+// selected mods, their contents and the game version. Re-injecting the same loadout
+// reuses the folder instead of extracting and rebuilding everything.
+
+// Default size cap for all cached overlays together (2 GiB)
+pub const DEFAULT_MAX_CACHE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+// Bump when the overlay format or build logic changes to invalidate old entries
+const CACHE_FORMAT_VERSION: &str = "overlay-v1";
+const CACHE_INDEX_FILE: &str = "cache_index.json";
+const STAGING_SUFFIX: &str = ".staging";
+const ACTIVE_FILE: &str = "active.txt";
+// Staging folders untouched for this long belong to a build that was interrupted
+const STALE_STAGING: Duration = Duration::from_secs(60 * 60);
+
+// md5 of mod files keyed by (path, size, mtime) so unchanged fantomes are not re-read
+type FileHashes = HashMap<(PathBuf, u64, u64), String>;
+static FILE_HASHES: Lazy<Arc<Mutex<FileHashes>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(HashMap::new()))
+});
+
+// Serializes access to cache_index.json across injector instances
+static CACHE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub size_bytes: u64,
+    pub created_at: i64,
+    pub last_used: i64,
+}
+
+pub struct OverlayCache {
+    cache_dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl OverlayCache {
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self::with_max_bytes(cache_dir, DEFAULT_MAX_CACHE_BYTES)
+    }
+
+    pub fn with_max_bytes(cache_dir: PathBuf, max_bytes: u64) -> Self {
+        Self { cache_dir, max_bytes }
+    }
+
+    // Build the cache key for a set of mod files against the installed game
+    pub fn compute_key(&self, mod_files: &[PathBuf], game_path: &Path) -> Result<String, InjectionError> {
+        let start = Instant::now();
+        let mut parts = Vec::with_capacity(mod_files.len());
+        for path in mod_files {
+            let name = path.file_name()
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            parts.push(format!("{}:{}", name, hash_file(path)?));
+        }
+
+        let mut context = md5::Context::new();
+        context.consume(CACHE_FORMAT_VERSION.as_bytes());
+        context.consume(game_version_fingerprint(game_path).as_bytes());
+        for part in &parts {
+            context.consume(b"\n");
+            context.consume(part.as_bytes());
+        }
+        let key = format!("{:x}", context.compute());
+        println!("Computed overlay cache key {} in {:?}", key, start.elapsed());
+        Ok(key)
+    }
+
+    pub fn entry_dir(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(key)
+    }
+
+    // Folder to build a new overlay into before it is committed with insert()
+    pub fn staging_dir(&self, key: &str) -> Result<PathBuf, InjectionError> {
+        let dir = self.cache_dir.join(format!("{}{}", key, STAGING_SUFFIX));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    // Return the cached overlay for key and mark it as recently used
+    pub fn lookup(&self, key: &str) -> Option<PathBuf> {
+        let _guard = CACHE_LOCK.lock().unwrap();
+        let mut index = self.load_index();
+        let dir = self.entry_dir(key);
+        let entry = index.get_mut(key)?;
+        if !dir.is_dir() {
+            index.remove(key);
+            let _ = self.save_index(&index);
+            return None;
+        }
+        entry.last_used = chrono::Utc::now().timestamp();
+        let _ = self.save_index(&index);
+        Some(dir)
+    }
+
+    // Check for a cached overlay without updating its usage
+    pub fn contains(&self, key: &str) -> bool {
+        let _guard = CACHE_LOCK.lock().unwrap();
+        self.load_index().contains_key(key) && self.entry_dir(key).is_dir()
+    }
+
+    // Overlay that runoverlay was last started with
+    pub fn active(&self) -> Option<PathBuf> {
+        let key = fs::read_to_string(self.cache_dir.join(ACTIVE_FILE)).ok()?;
+        let dir = self.entry_dir(key.trim());
+        if dir.is_dir() { Some(dir) } else { None }
+    }
+
+    pub fn set_active(&self, key: &str) {
+        let _ = fs::write(self.cache_dir.join(ACTIVE_FILE), key);
+    }
+
+    // Move a finished staging folder into the cache and evict old entries over the cap
+    pub fn insert(&self, key: &str, staging_dir: &Path) -> Result<PathBuf, InjectionError> {
+        let _guard = CACHE_LOCK.lock().unwrap();
+        let dir = self.entry_dir(key);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::rename(staging_dir, &dir)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let mut index = self.load_index();
+        index.insert(key.to_string(), CacheEntry {
+            size_bytes: dir_size(&dir),
+            created_at: now,
+            last_used: now,
+        });
+        self.evict(&mut index, key);
+        self.save_index(&index)?;
+        Ok(dir)
+    }
+
+    // Least recently used eviction, never touching the entry that is about to be used
+    // or the one that is currently running
+    fn evict(&self, index: &mut HashMap<String, CacheEntry>, keep: &str) {
+        let active = fs::read_to_string(self.cache_dir.join(ACTIVE_FILE)).unwrap_or_default();
+        let mut total: u64 = index.values().map(|e| e.size_bytes).sum();
+        let mut by_age: Vec<(String, i64)> = index.iter()
+            .filter(|(key, _)| key.as_str() != keep && key.as_str() != active.trim())
+            .map(|(key, entry)| (key.clone(), entry.last_used))
+            .collect();
+        by_age.sort_by_key(|(_, last_used)| *last_used);
+
+        for (key, _) in by_age {
+            if total <= self.max_bytes {
+                break;
+            }
+            let dir = self.entry_dir(&key);
+            if dir.exists() && fs::remove_dir_all(&dir).is_err() {
+                // Probably still locked by a running overlay, try again next time
+                continue;
+            }
+            if let Some(entry) = index.remove(&key) {
+                total = total.saturating_sub(entry.size_bytes);
+                println!("Evicted cached overlay {} ({} bytes)", key, entry.size_bytes);
+            }
+        }
+
+        // Drop folders that are not tracked anymore (interrupted builds, old entries).
+        // Staging folders may belong to a build that is still running, only stale ones go.
+        if let Ok(entries) = fs::read_dir(&self.cache_dir) {
+            for entry in entries.filter_map(Result::ok) {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !path.is_dir() || index.contains_key(&name) {
+                    continue;
+                }
+                if name.ends_with(STAGING_SUFFIX) && !is_stale(&path) {
+                    continue;
+                }
+                let _ = fs::remove_dir_all(&path);
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn clear(&self) -> Result<(), InjectionError> {
+        let _guard = CACHE_LOCK.lock().unwrap();
+        if self.cache_dir.exists() {
+            fs::remove_dir_all(&self.cache_dir)?;
+        }
+        fs::create_dir_all(&self.cache_dir)?;
+        Ok(())
+    }
+
+    fn load_index(&self) -> HashMap<String, CacheEntry> {
+        fs::read_to_string(self.cache_dir.join(CACHE_INDEX_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &HashMap<String, CacheEntry>) -> Result<(), InjectionError> {
+        fs::create_dir_all(&self.cache_dir)?;
+        let content = serde_json::to_string_pretty(index)
+            .map_err(|e| InjectionError::ConfigError(format!("Failed to serialize overlay cache index: {}", e)))?;
+        fs::write(self.cache_dir.join(CACHE_INDEX_FILE), content)?;
+        Ok(())
+    }
+}
+
+pub fn modified_secs(metadata: &fs::Metadata) -> u64 {
+    metadata.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+// md5 of a mod file (or every file of a mod folder), memoized by size and mtime
+pub fn hash_file(path: &Path) -> Result<String, InjectionError> {
+    let metadata = fs::metadata(path)?;
+    if metadata.is_dir() {
+        let mut context = md5::Context::new();
+        let mut files: Vec<PathBuf> = WalkDir::new(path).into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        files.sort();
+        for file in files {
+            let rel = file.strip_prefix(path).unwrap_or(&file).to_string_lossy().to_string();
+            context.consume(rel.as_bytes());
+            context.consume(hash_file(&file)?.as_bytes());
+        }
+        return Ok(format!("{:x}", context.compute()));
+    }
+
+    let cache_key = (path.to_path_buf(), metadata.len(), modified_secs(&metadata));
+    if let Some(hash) = FILE_HASHES.lock().unwrap().get(&cache_key) {
+        return Ok(hash.clone());
+    }
+
+    let mut file = fs::File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.consume(&buffer[..read]);
+    }
+    let hash = format!("{:x}", context.compute());
+    FILE_HASHES.lock().unwrap().insert(cache_key, hash.clone());
+    Ok(hash)
+}
+
+// Changes whenever the game is patched
+fn game_version_fingerprint(game_path: &Path) -> String {
+    let exe = game_path.join("League of Legends.exe");
+    let metadata = fs::metadata(&exe)
+        .or_else(|_| fs::metadata(game_path.join("DATA").join("FINAL")));
+    match metadata {
+        Ok(metadata) => format!("{}:{}", metadata.len(), modified_secs(&metadata)),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+// Nothing in the folder was written to for STALE_STAGING
+fn is_stale(dir: &Path) -> bool {
+    let newest = WalkDir::new(dir).into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|e| e.metadata().ok())
+        .filter_map(|m| m.modified().ok())
+        .max();
+    match newest {
+        Some(modified) => modified.elapsed().is_ok_and(|age| age > STALE_STAGING),
+        None => true,
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    WalkDir::new(dir).into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction_keeps_builds_in_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = OverlayCache::with_max_bytes(dir.path().to_path_buf(), 0);
+
+        let old = cache.staging_dir("old").unwrap();
+        fs::write(old.join("overlay.wad.client"), b"old overlay").unwrap();
+        cache.insert("old", &old).unwrap();
+
+        // Another injection is still building into its staging folder
+        let building = cache.staging_dir("building").unwrap();
+        fs::write(building.join("overlay.wad.client"), b"half written").unwrap();
+        let leftover = dir.path().join("untracked");
+        fs::create_dir_all(&leftover).unwrap();
+
+        let new = cache.staging_dir("new").unwrap();
+        fs::write(new.join("overlay.wad.client"), b"new overlay").unwrap();
+        cache.insert("new", &new).unwrap();
+
+        assert!(cache.contains("new"));
+        assert!(!cache.contains("old"));
+        assert!(!cache.entry_dir("old").exists());
+        assert!(building.join("overlay.wad.client").exists());
+        assert!(!leftover.exists());
+    }
+}
@@ -0,0 +1,415 @@
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::injection::error::InjectionError;
+use crate::injection::patcher::active_patcher_state;
+use crate::injection::types::{ModState, Skin};
+
+// Single-flight coordinator for injections
+// Manual injects and the LCU watcher both go through here. Only one injection runs
+// at a time; while it runs, newer requests replace older queued ones (only the latest
+// loadout matters) and a request for the loadout that is already being injected or
+// already active is not injected again.
+
+static COORDINATOR: Lazy<Coordinator> = Lazy::new(|| Coordinator {
+    state: Mutex::new(CoordinatorState::default()),
+    changed: Condvar::new(),
+});
+
+// Longest a request waits for the injection ahead of it; every step of that one has
+// its own time limit, so this only trips when something is stuck for good
+const MAX_WAIT: Duration = Duration::from_secs(15 * 60);
+
+// What happened to a submitted injection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionOutcome {
+    Injected,
+    AlreadyActive, // Same loadout is already running, nothing was done
+    Superseded,    // A newer request replaced this one while it was queued
+}
+
+// Snapshot returned by the get_injection_state command
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectionStateSnapshot {
+    pub state: ModState,
+    pub running: Option<InjectionSummary>,
+    pub queued: Option<InjectionSummary>,
+    pub active_skins: Vec<Skin>, // Loadout of the last successful injection
+    pub patcher: Option<ModState>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectionSummary {
+    pub source: String,
+    pub skins: Vec<Skin>,
+    pub started_at: i64,
+}
+
+struct Ticket {
+    id: u64,
+    signature: String,
+    summary: InjectionSummary,
+}
+
+#[derive(Default)]
+struct CoordinatorState {
+    next_id: u64,
+    running: Option<Ticket>,
+    queued: Option<Ticket>,
+    active: Option<(String, Vec<Skin>)>,
+    // Error of the last finished injection (None on success), shared with callers that joined it
+    last_finished: Option<(u64, Option<InjectionError>)>,
+}
+
+struct Coordinator {
+    state: Mutex<CoordinatorState>,
+    changed: Condvar,
+}
+
+// Identifies a loadout: same game, same skins (in any order), same priority
+fn signature(game_path: &str, skins: &[Skin], mod_priority: &[String]) -> String {
+    let mut parts: Vec<String> = skins.iter()
+        .map(|s| format!("{}:{}:{:?}:{:?}", s.champion_id, s.skin_id, s.chroma_id, s.fantome_path))
+        .collect();
+    parts.sort();
+    parts.dedup();
+    format!("{}|{}|{}", game_path, parts.join(","), mod_priority.join(","))
+}
+
+// InjectionError isn't Clone (io::Error), keep the kinds callers branch on
+fn share_error(e: &InjectionError) -> InjectionError {
+    match e {
+        InjectionError::Aborted(msg) => InjectionError::Aborted(msg.clone()),
+        InjectionError::Timeout(msg) => InjectionError::Timeout(msg.clone()),
+        other => InjectionError::ProcessError(other.to_string()),
+    }
+}
+
+fn lock_state() -> MutexGuard<'static, CoordinatorState> {
+    COORDINATOR.state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Wait for the next change, or fail once deadline has passed
+fn wait_until<'a>(
+    state: MutexGuard<'a, CoordinatorState>,
+    deadline: Instant,
+) -> Result<MutexGuard<'a, CoordinatorState>, MutexGuard<'a, CoordinatorState>> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return Err(state);
+    }
+    let (state, _) = COORDINATOR.changed.wait_timeout(state, remaining).unwrap_or_else(|e| e.into_inner());
+    Ok(state)
+}
+
+// Frees the running slot and wakes every waiter when the injection is over,
+// also when inject() panicked
+struct RunningGuard {
+    id: u64,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        let mut state = lock_state();
+        if state.running.as_ref().is_some_and(|running| running.id == self.id) {
+            state.running = None;
+        }
+        if !matches!(&state.last_finished, Some((id, _)) if *id == self.id) {
+            state.last_finished = Some((self.id, Some(InjectionError::ProcessError("Injection stopped unexpectedly".into()))));
+        }
+        COORDINATOR.changed.notify_all();
+    }
+}
+
+fn patcher_alive() -> bool {
+    matches!(active_patcher_state(), Some(ModState::Running) | Some(ModState::Busy))
+}
+
+// Run inject through the coordinator. Blocks until this request ran, was merged
+// into a newer one or turned out to be a no-op.
+pub fn submit<F>(
+    source: &str,
+    game_path: &str,
+    skins: &[Skin],
+    mod_priority: &[String],
+    inject: F,
+) -> Result<InjectionOutcome, InjectionError>
+where
+    F: FnOnce() -> Result<(), InjectionError>,
+{
+    submit_within(MAX_WAIT, source, game_path, skins, mod_priority, inject)
+}
+
+fn submit_within<F>(
+    max_wait: Duration,
+    source: &str,
+    game_path: &str,
+    skins: &[Skin],
+    mod_priority: &[String],
+    inject: F,
+) -> Result<InjectionOutcome, InjectionError>
+where
+    F: FnOnce() -> Result<(), InjectionError>,
+{
+    let coordinator = &*COORDINATOR;
+    let signature = signature(game_path, skins, mod_priority);
+    let deadline = Instant::now() + max_wait;
+    let mut state = lock_state();
+
+    // Same loadout as the one being injected right now: wait for it and share its result
+    if let Some(running) = state.running.as_ref().filter(|r| r.signature == signature) {
+        let joined = running.id;
+        println!("[Injection] {} request joins the running injection of the same skins", source);
+        // This is now the latest request, so whatever was queued is outdated
+        if state.queued.take().is_some() {
+            coordinator.changed.notify_all();
+        }
+        loop {
+            state = match wait_until(state, deadline) {
+                Ok(state) => state,
+                Err(_) => return Err(InjectionError::Timeout(format!(
+                    "{} request gave up waiting for the running injection", source
+                ))),
+            };
+            if let Some((id, error)) = &state.last_finished {
+                if *id == joined {
+                    return match error {
+                        Some(e) => Err(share_error(e)),
+                        None => Ok(InjectionOutcome::Injected),
+                    };
+                }
+            }
+        }
+    }
+
+    if state.running.is_none()
+        && state.active.as_ref().map(|(active, _)| active == &signature).unwrap_or(false)
+        && patcher_alive()
+    {
+        println!("[Injection] Skipping {} request, these skins are already active", source);
+        return Ok(InjectionOutcome::AlreadyActive);
+    }
+
+    state.next_id += 1;
+    let id = state.next_id;
+    let ticket = Ticket {
+        id,
+        signature: signature.clone(),
+        summary: InjectionSummary {
+            source: source.to_string(),
+            skins: skins.to_vec(),
+            started_at: chrono::Utc::now().timestamp(),
+        },
+    };
+
+    if state.running.is_some() {
+        // Take the queue slot; whoever held it is superseded
+        if state.queued.replace(ticket).is_some() {
+            coordinator.changed.notify_all();
+        }
+        println!("[Injection] {} request queued behind the running injection", source);
+        loop {
+            state = match wait_until(state, deadline) {
+                Ok(state) => state,
+                Err(mut state) => {
+                    // Nobody would be waiting for it anymore
+                    if state.queued.as_ref().is_some_and(|queued| queued.id == id) {
+                        state.queued = None;
+                    }
+                    return Err(InjectionError::Timeout(format!(
+                        "{} request gave up waiting for the running injection", source
+                    )));
+                }
+            };
+            match &state.queued {
+                Some(queued) if queued.id == id => {
+                    if state.running.is_none() {
+                        break;
+                    }
+                }
+                _ => {
+                    println!("[Injection] Queued {} request was replaced by a newer one", source);
+                    return Ok(InjectionOutcome::Superseded);
+                }
+            }
+        }
+        let mut ticket = state.queued.take().unwrap();
+        ticket.summary.started_at = chrono::Utc::now().timestamp();
+        state.running = Some(ticket);
+    } else {
+        state.running = Some(ticket);
+    }
+
+    // The previous injection may have applied this loadout while we were queued
+    if state.active.as_ref().map(|(active, _)| active == &signature).unwrap_or(false) && patcher_alive() {
+        state.running = None;
+        coordinator.changed.notify_all();
+        println!("[Injection] Skipping {} request, these skins are already active", source);
+        return Ok(InjectionOutcome::AlreadyActive);
+    }
+    drop(state);
+
+    let guard = RunningGuard { id };
+    let result = inject();
+
+    let mut state = lock_state();
+    // On failure the previous overlay is restored, so the previous loadout stays active
+    if result.is_ok() {
+        state.active = Some((signature, skins.to_vec()));
+    }
+    state.last_finished = Some((id, result.as_ref().err().map(share_error)));
+    drop(state);
+    drop(guard);
+
+    result.map(|_| InjectionOutcome::Injected)
+}
+
+pub fn snapshot() -> InjectionStateSnapshot {
+    let state = lock_state();
+    let patcher = active_patcher_state();
+    let overall = if state.running.is_some() {
+        ModState::Busy
+    } else {
+        patcher.unwrap_or(ModState::Idle)
+    };
+    InjectionStateSnapshot {
+        state: overall,
+        running: state.running.as_ref().map(|t| t.summary.clone()),
+        queued: state.queued.as_ref().map(|t| t.summary.clone()),
+        active_skins: state.active.as_ref().map(|(_, skins)| skins.clone()).unwrap_or_default(),
+        patcher,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::thread;
+
+    use super::*;
+
+    // The coordinator is global, tests that queue behind each other can't overlap
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    fn skins(skin_id: u32) -> Vec<Skin> {
+        vec![Skin { champion_id: 103, skin_id, chroma_id: None, fantome_path: None }]
+    }
+
+    type Release = mpsc::Sender<Result<(), InjectionError>>;
+    type Submitted = thread::JoinHandle<Result<InjectionOutcome, InjectionError>>;
+
+    // An injection of skin_id into game that runs until the returned sender is used
+    // (Ok(()) or an Err to fail with)
+    fn blocking(game: &'static str, skin_id: u32) -> (Release, Submitted) {
+        let (release, wait) = mpsc::channel();
+        let (started, has_started) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            submit("test", game, &skins(skin_id), &[], || {
+                started.send(()).unwrap();
+                wait.recv().unwrap()
+            })
+        });
+        has_started.recv().unwrap();
+        (release, handle)
+    }
+
+    fn queued_skin() -> Option<u32> {
+        lock_state().queued.as_ref().map(|ticket| ticket.summary.skins[0].skin_id)
+    }
+
+    fn wait_for_queued(skin_id: u32) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while queued_skin() != Some(skin_id) {
+            assert!(Instant::now() < deadline, "skin {} never got queued", skin_id);
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn panicking_injection_frees_the_slot() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let skins = skins(1);
+        let panicked = std::panic::catch_unwind(|| {
+            submit("test", "panic-game", &skins, &[], || panic!("injection blew up"))
+        });
+        assert!(panicked.is_err());
+        assert!(lock_state().running.is_none());
+
+        // The next request runs right away instead of waiting on the dead one
+        let outcome = submit("test", "panic-game", &skins, &[], || Ok(())).unwrap();
+        assert_eq!(outcome, InjectionOutcome::Injected);
+    }
+
+    #[test]
+    fn newer_request_supersedes_the_queued_one() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let (release, running) = blocking("supersede-game", 1);
+
+        let first = thread::spawn(|| submit("test", "supersede-game", &skins(2), &[], || Ok(())));
+        wait_for_queued(2);
+        let second = thread::spawn(|| submit("test", "supersede-game", &skins(3), &[], || Ok(())));
+        assert_eq!(first.join().unwrap().unwrap(), InjectionOutcome::Superseded);
+        assert_eq!(queued_skin(), Some(3));
+
+        release.send(Ok(())).unwrap();
+        assert_eq!(running.join().unwrap().unwrap(), InjectionOutcome::Injected);
+        assert_eq!(second.join().unwrap().unwrap(), InjectionOutcome::Injected);
+        assert_eq!(snapshot().active_skins[0].skin_id, 3);
+    }
+
+    #[test]
+    fn queued_request_gives_up() {
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let (release, running) = blocking("give-up-game", 1);
+
+        let wait = Duration::from_millis(200);
+        let result = submit_within(wait, "test", "give-up-game", &skins(2), &[], || {
+            panic!("a request that gave up must not run")
+        });
+        assert!(matches!(result, Err(InjectionError::Timeout(_))), "{:?}", result);
+        // Nothing is left behind in the queue
+        assert_eq!(queued_skin(), None);
+
+        release.send(Ok(())).unwrap();
+        assert_eq!(running.join().unwrap().unwrap(), InjectionOutcome::Injected);
+    }
+
+    // AlreadyActive needs a live patcher, a shell reading stdin stands in for it
+    #[cfg(unix)]
+    #[test]
+    fn queued_request_for_the_active_loadout_is_skipped() {
+        use std::process::Command;
+
+        use crate::injection::patcher::{
+            set_active_patcher, stop_active_patcher, PatcherSupervisor,
+        };
+
+        let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let supervisor = PatcherSupervisor::start(Box::new(|| {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg("read _");
+            command
+        }), None).unwrap();
+        set_active_patcher(supervisor);
+
+        let first = submit("test", "active-game", &skins(1), &[], || Ok(())).unwrap();
+        assert_eq!(first, InjectionOutcome::Injected);
+        let again = submit("test", "active-game", &skins(1), &[], || Ok(())).unwrap();
+        assert_eq!(again, InjectionOutcome::AlreadyActive);
+
+        // Queued behind another loadout that then fails, so skin 1 is still active on its turn
+        let (release, running) = blocking("active-game", 2);
+        let queued = thread::spawn(|| submit("test", "active-game", &skins(1), &[], || {
+            panic!("the active loadout must not be injected again")
+        }));
+        wait_for_queued(1);
+        release.send(Err(InjectionError::ProcessError("patcher failed".into()))).unwrap();
+        assert!(running.join().unwrap().is_err());
+        assert_eq!(queued.join().unwrap().unwrap(), InjectionOutcome::AlreadyActive);
+
+        stop_active_patcher();
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::injection::error::InjectionError;
+use crate::injection::hashes::HashTable;
+use crate::injection::overlay::WadIndex;
+use crate::injection::utils::global_index_for;
+
+// Champion detection for custom skins
+// A skin overrides its champion's WAD (Ahri.wad.client, Ahri.en_US.wad.client) and files
+// under data/characters/<name>/ or assets/characters/<name>/. Each hint is a vote; the
+// names are then matched against the champion JSON (alias, name or folder).
+
+// A WAD name says more than any single file inside it
+const WAD_NAME_WEIGHT: usize = 100;
+
+// Candidate champion names, lowercase, most likely first
+pub fn champion_candidates(path: &Path, hashes: Option<&HashTable>) -> Result<Vec<String>, InjectionError> {
+    let index = WadIndex::from_mod_path(path)?;
+    let mut votes: HashMap<String, usize> = HashMap::new();
+
+    for mounted in index.mounts.values() {
+        let mount = mounted.mount_name();
+        // Locale WADs: "ahri.en_us"
+        let base = mount.split('.').next().unwrap_or(&mount).to_string();
+        *votes.entry(base).or_default() += WAD_NAME_WEIGHT;
+
+        for name in mounted.archive.entries.keys() {
+            let entry_path = mounted.archive.names.get(name).map(String::as_str)
+                .or_else(|| hashes.and_then(|table| table.resolve(*name)));
+            if let Some(champion) = entry_path.and_then(character_from_path) {
+                *votes.entry(champion).or_default() += 1;
+            }
+        }
+    }
+
+    let mut candidates: Vec<(String, usize)> = votes.into_iter().collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(candidates.into_iter().map(|(name, _)| name).collect())
+}
+
+// "data/characters/ahri/skins/skin1.bin" -> "ahri"
+fn character_from_path(path: &str) -> Option<String> {
+    let lower = path.replace('\\', "/").to_lowercase();
+    let mut parts = lower.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("data") | Some("assets"), Some("characters"), Some(name)) if !name.is_empty() => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+// Champion (id, folder name) a mod is made for, if it can be told from its contents
+pub fn detect_champion(
+    path: &Path,
+    champions_dir: &Path,
+    hashes: Option<&HashTable>,
+) -> Result<Option<(u32, String)>, InjectionError> {
+    let candidates = champion_candidates(path, hashes)?;
+    let index = global_index_for(champions_dir)?;
+    let index = index.lock().unwrap();
+    for candidate in &candidates {
+        if let Some(found) = index.find_champion(candidate) {
+            println!("Detected champion {} ({}) for {}", found.1, found.0, path.display());
+            return Ok(Some(found));
+        }
+    }
+    println!("Could not detect the champion of {} (candidates: {:?})", path.display(), candidates);
+    Ok(None)
+}
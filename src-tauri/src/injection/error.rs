@@ -7,9 +7,11 @@ pub enum InjectionError {
     IoError(io::Error),
     InvalidGamePath(String),
     MissingFantomeFile(String),
+    InvalidFantome(String),
     ProcessError(String),
     ConfigError(String),
     OverlayError(String),
+    WadError(String),
     Timeout(String),
     Aborted(String),
     WalkdirError(walkdir::Error),
@@ -22,9 +24,11 @@ impl std::fmt::Display for InjectionError {
             Self::IoError(err) => write!(f, "IO Error: {}", err),
             Self::InvalidGamePath(msg) => write!(f, "Invalid game path: {}", msg),
             Self::MissingFantomeFile(msg) => write!(f, "Missing fantome file: {}", msg),
+            Self::InvalidFantome(msg) => write!(f, "Invalid fantome file: {}", msg),
             Self::ProcessError(msg) => write!(f, "Process error: {}", msg),
             Self::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
             Self::OverlayError(msg) => write!(f, "Overlay error: {}", msg),
+            Self::WadError(msg) => write!(f, "WAD error: {}", msg),
             Self::Timeout(msg) => write!(f, "Timeout: {}", msg),
             Self::Aborted(msg) => write!(f, "Aborted: {}", msg),
             Self::WalkdirError(err) => write!(f, "Walkdir error: {}", err),
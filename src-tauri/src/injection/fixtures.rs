@@ -0,0 +1,87 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::injection::error::InjectionError;
+use crate::injection::wad::{self, WadArchive, WadEntry};
+
+// Throwaway League install for running the injection flow without the real game
+// Only what the injector looks at is created: Game/League of Legends.exe and
+// WADs under Game/DATA/FINAL, written in the same v3.4 format the game ships.
+pub struct FakeLeague {
+    root_path: PathBuf,
+}
+
+impl FakeLeague {
+    pub fn create(root_path: &Path) -> Result<Self, InjectionError> {
+        let game_path = root_path.join("Game");
+        fs::create_dir_all(game_path.join("DATA").join("FINAL").join("Champions"))?;
+        fs::write(game_path.join("League of Legends.exe"), b"fake league")?;
+        Ok(Self { root_path: root_path.to_path_buf() })
+    }
+
+    // League root, as passed to SkinInjector
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    pub fn game_path(&self) -> PathBuf {
+        self.root_path.join("Game")
+    }
+
+    // Write Game/DATA/FINAL/Champions/<champion>.wad.client with the given files
+    pub fn add_champion_wad(&self, champion: &str, files: &[(&str, &[u8])]) -> Result<PathBuf, InjectionError> {
+        let path = self.game_path()
+            .join("DATA").join("FINAL").join("Champions")
+            .join(format!("{}.wad.client", champion));
+        build_wad(files).write_to_file(&path)?;
+        Ok(path)
+    }
+}
+
+// Contents of a file inside a WAD, e.g. to check what an overlay ended up with
+pub fn read_wad_file(wad_path: &Path, path: &str) -> Result<Option<Vec<u8>>, InjectionError> {
+    let archive = WadArchive::read_from_file(wad_path)?;
+    archive.entries.get(&wad::hash_path(path))
+        .map(|entry| entry.decompressed())
+        .transpose()
+}
+
+fn build_wad(files: &[(&str, &[u8])]) -> WadArchive {
+    let mut archive = WadArchive::new();
+    for (path, bytes) in files {
+        let hash = wad::hash_path(path);
+        archive.entries.insert(hash, WadEntry::from_raw(bytes.to_vec()));
+        archive.names.insert(hash, path.to_lowercase());
+    }
+    archive
+}
+
+// Write a minimal .fantome that replaces files of <champion>.wad.client
+pub fn write_fantome(path: &Path, champion: &str, files: &[(&str, &[u8])]) -> Result<(), InjectionError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let name = path.file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let info = serde_json::json!({
+        "Name": name,
+        "Author": "test-support",
+        "Version": "1.0",
+        "Description": format!("Fake {} skin", champion),
+    });
+
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = FileOptions::default();
+    zip.start_file("META/info.json", options)?;
+    zip.write_all(info.to_string().as_bytes())?;
+    for (file_path, bytes) in files {
+        zip.start_file(format!("WAD/{}.wad.client/{}", champion, file_path), options)?;
+        zip.write_all(bytes)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+use once_cell::sync::Lazy;
+
+use crate::injection::error::InjectionError;
+
+// WAD path-hash dictionary (CommunityDragon hashes.game.txt)
+// Each line is "<16 hex digit xxh64> <path>". All paths live in one string buffer with a
+// table of (hash, offset, length) sorted by hash on top, so a few million entries cost
+// little more than the text itself and a lookup is a binary search.
+
+// Dictionary files are hashes.game.txt or its split parts (hashes.game.txt.0, .1, ...)
+pub const HASH_FILE_PREFIX: &str = "hashes.game.txt";
+
+type LoadedTable = (PathBuf, Arc<HashTable>);
+static HASH_TABLE: Lazy<RwLock<Option<LoadedTable>>> = Lazy::new(|| RwLock::new(None));
+// Held while a dictionary is read, so callers arriving meanwhile wait for it instead of reading it again
+static LOADING: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Default)]
+pub struct HashTable {
+    entries: Vec<(u64, u32, u32)>,
+    paths: String,
+}
+
+pub fn is_hash_file(name: &str) -> bool {
+    name.starts_with(HASH_FILE_PREFIX) && !name.ends_with(".tmp")
+}
+
+impl HashTable {
+    // Load every dictionary file in dir; later files win for duplicate hashes
+    pub fn load_dir(dir: &Path) -> Result<Self, InjectionError> {
+        let start = Instant::now();
+        let mut files: Vec<_> = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .filter(|e| is_hash_file(&e.file_name().to_string_lossy()))
+            .map(|e| e.path())
+            .collect();
+        files.sort();
+
+        let mut table = Self::default();
+        for file in &files {
+            table.add_text(&fs::read_to_string(file)?)?;
+        }
+        table.finish();
+        println!("Loaded {} WAD path hashes from {} files in {:?}", table.len(), files.len(), start.elapsed());
+        Ok(table)
+    }
+
+    fn add_text(&mut self, text: &str) -> Result<(), InjectionError> {
+        for line in text.lines() {
+            let (hash, path) = match line.split_once(' ') {
+                Some(parts) => parts,
+                None => continue,
+            };
+            let hash = match u64::from_str_radix(hash, 16) {
+                Ok(hash) => hash,
+                Err(_) => continue,
+            };
+            let offset = u32::try_from(self.paths.len())
+                .map_err(|_| InjectionError::ProcessError("Hash dictionary is too large".into()))?;
+            self.paths.push_str(path);
+            self.entries.push((hash, offset, path.len() as u32));
+        }
+        Ok(())
+    }
+
+    // Sort for lookups, keeping the last entry of each hash
+    fn finish(&mut self) {
+        // Stable sort keeps file order within a hash
+        self.entries.sort_by_key(|(hash, _, _)| *hash);
+        let mut deduped: Vec<(u64, u32, u32)> = Vec::with_capacity(self.entries.len());
+        for entry in self.entries.drain(..) {
+            match deduped.last_mut() {
+                Some(last) if last.0 == entry.0 => *last = entry,
+                _ => deduped.push(entry),
+            }
+        }
+        deduped.shrink_to_fit();
+        self.entries = deduped;
+        self.paths.shrink_to_fit();
+    }
+
+    pub fn resolve(&self, hash: u64) -> Option<&str> {
+        let index = self.entries.binary_search_by_key(&hash, |(hash, _, _)| *hash).ok()?;
+        let (_, offset, len) = self.entries[index];
+        self.paths.get(offset as usize..(offset + len) as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+fn loaded(dir: &Path) -> Option<Arc<HashTable>> {
+    HASH_TABLE.read().unwrap().as_ref()
+        .filter(|(loaded_dir, _)| loaded_dir == dir)
+        .map(|(_, table)| table.clone())
+}
+
+// Dictionary loaded from dir. Normally preload_hash_table has read it already,
+// otherwise it is read now. None while no dictionary has been downloaded.
+pub fn hash_table(dir: &Path) -> Option<Arc<HashTable>> {
+    if let Some(table) = loaded(dir) {
+        return Some(table);
+    }
+    let _loading = LOADING.lock().unwrap_or_else(|e| e.into_inner());
+    loaded(dir).or_else(|| load(dir).ok().flatten())
+}
+
+// Read the dictionary on a background thread, so the first lookup doesn't pay for it
+pub fn preload_hash_table(dir: PathBuf) {
+    std::thread::spawn(move || {
+        hash_table(&dir);
+    });
+}
+
+// Read the dictionary again, e.g. after an update replaced some of its files
+pub fn reload_hash_table(dir: &Path) -> Result<Option<Arc<HashTable>>, InjectionError> {
+    let _loading = LOADING.lock().unwrap_or_else(|e| e.into_inner());
+    load(dir)
+}
+
+fn load(dir: &Path) -> Result<Option<Arc<HashTable>>, InjectionError> {
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    let table = Arc::new(HashTable::load_dir(dir)?);
+    if table.len() == 0 {
+        return Ok(None);
+    }
+    *HASH_TABLE.write().unwrap() = Some((dir.to_path_buf(), table.clone()));
+    Ok(Some(table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_hashes_and_skips_malformed_lines() {
+        let mut table = HashTable::default();
+        table.add_text("\
+00000000000000a1 assets/characters/ahri/skins/skin01.bin
+no-space-on-this-line
+
+zzzzzzzzzzzzzzzz assets/not/a/hash.bin
+00000000000000b2 data/characters/ahri/ahri.bin
+00000000000000a1 assets/characters/ahri/skins/skin02.bin
+").unwrap();
+        // A later file overrides an earlier one
+        table.add_text("00000000000000B2 data/characters/ahri/ahri_updated.bin\n").unwrap();
+        table.finish();
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.resolve(0xa1), Some("assets/characters/ahri/skins/skin02.bin"));
+        assert_eq!(table.resolve(0xb2), Some("data/characters/ahri/ahri_updated.bin"));
+        assert_eq!(table.resolve(0xc3), None);
+    }
+}
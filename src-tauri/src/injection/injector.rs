@@ -1,20 +1,25 @@
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use crate::injection::backend::{ModToolsBackend, ModToolsExe};
+use crate::injection::cache::OverlayCache;
+use crate::injection::coordinator::{self, InjectionOutcome};
 use crate::injection::error::InjectionError;
-use crate::injection::types::{Skin, ModState};
+use crate::injection::job::{InjectionJob, InjectionStep};
+use crate::injection::overlay::{build_overlay, find_conflicts, sort_by_priority, WadIndex};
+use crate::injection::patcher::{set_active_patcher, stop_active_patcher, PatcherSupervisor};
+use crate::injection::transaction::ModsTransaction;
+use crate::injection::normalize::{mod_name, normalize_folder, normalize_wad_file, ModKind};
+use crate::injection::validation::{copy_entry, validate_mod};
+use crate::injection::utils::lookup_skin;
+use crate::injection::types::{Skin, ModState, InjectionPlan, InjectionTimeouts, PlannedMod};
 use tauri::{AppHandle, Manager, Emitter};
 use walkdir::WalkDir;
 use zip::ZipArchive;
 use std::env;
 use memmap2::MmapOptions;
+use std::sync::Arc;
 use std::time::Instant;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-
-// Define Windows-specific constants at the module level
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 // Main skin injector class - simplified without profiles
 pub struct SkinInjector {
@@ -25,13 +30,29 @@ pub struct SkinInjector {
     game_path: PathBuf,  // Store the Game subdirectory path
     status: String,
     log_file: Option<File>,
-    mod_tools_path: Option<PathBuf>, // Add mod_tools path
-    champion_names: std::collections::HashMap<u32, String>, // Add cache for champion names
+    backend: Arc<dyn ModToolsBackend>, // Runs mod-tools (or a fake in tests)
     app_handle: Option<AppHandle>,
+    mod_priority: Vec<String>, // Mod names, highest priority first
+    timeouts: InjectionTimeouts,
+    job: Option<InjectionJob>, // Set while inject_skins runs
 }
 
 impl SkinInjector {
     pub fn new(app_handle: &AppHandle, root_path: &str) -> Result<Self, InjectionError> {
+        let mut injector = Self::for_planning(app_handle, root_path)?;
+
+        // Create directories needed
+        fs::create_dir_all(injector.app_dir.join("mods"))?;
+        fs::create_dir_all(injector.app_dir.join("temp"))?;
+
+        // Create log file
+        injector.log_file = Some(File::create(injector.app_dir.join("log.txt"))?);
+        Ok(injector)
+    }
+
+    // Injector for a dry run: only resolves paths. Nothing is created, and the log is
+    // appended to, since an injection running meanwhile is writing it.
+    pub fn for_planning(app_handle: &AppHandle, root_path: &str) -> Result<Self, InjectionError> {
         // Get the app directory
         let app_dir = app_handle.path().app_data_dir()
             .map_err(|e| InjectionError::IoError(io::Error::new(io::ErrorKind::NotFound, format!("{}", e))))?;
@@ -45,16 +66,7 @@ impl SkinInjector {
             return Err(InjectionError::InvalidGamePath("Game\\League of Legends.exe not found".into()));
         }
         
-        // Create directories needed
-        fs::create_dir_all(app_dir.join("mods"))?;
-        fs::create_dir_all(app_dir.join("temp"))?;
-        
-        // Create log file
-        let log_path = app_dir.join("log.txt");
-        let log_file = File::create(&log_path)?;
-
-        // Initialize empty champion names cache
-        let champion_names = std::collections::HashMap::new();
+        let log_file = OpenOptions::new().append(true).open(app_dir.join("log.txt")).ok();
 
         // Look for mod-tools executable in multiple locations
         let mut mod_tools_path = None;
@@ -123,13 +135,68 @@ impl SkinInjector {
             root_path,
             game_path,
             status: String::new(),
-            log_file: Some(log_file),
-            mod_tools_path,
-            champion_names,
+            log_file,
+            backend: Arc::new(ModToolsExe::new(mod_tools_path)),
             app_handle: Some(app_handle.clone()),
+            mod_priority: Vec::new(),
+            timeouts: InjectionTimeouts::default(),
+            job: None,
         })
     }
     
+    // Injector without a Tauri app, working out of app_dir (no events are emitted)
+    #[cfg(feature = "test-support")]
+    pub fn headless(root_path: &Path, app_dir: &Path, backend: Arc<dyn ModToolsBackend>) -> Result<Self, InjectionError> {
+        let game_path = root_path.join("Game");
+        if !game_path.join("League of Legends.exe").exists() {
+            return Err(InjectionError::InvalidGamePath("Game\\League of Legends.exe not found".into()));
+        }
+        fs::create_dir_all(app_dir.join("mods"))?;
+        fs::create_dir_all(app_dir.join("temp"))?;
+
+        Ok(Self {
+            state: ModState::Uninitialized,
+            app_dir: app_dir.to_path_buf(),
+            root_path: root_path.to_path_buf(),
+            game_path,
+            status: String::new(),
+            log_file: None,
+            backend,
+            app_handle: None,
+            mod_priority: Vec::new(),
+            timeouts: InjectionTimeouts::default(),
+            job: None,
+        })
+    }
+
+    // Swap the mod-tools implementation, e.g. for a fake one
+    pub fn set_backend(&mut self, backend: Arc<dyn ModToolsBackend>) {
+        self.backend = backend;
+    }
+    
+    // Set which mods win when several override the same files (highest priority first)
+    pub fn set_mod_priority(&mut self, mod_priority: Vec<String>) {
+        self.mod_priority = mod_priority;
+    }
+    
+    pub fn set_timeouts(&mut self, timeouts: InjectionTimeouts) {
+        self.timeouts = timeouts;
+    }
+    
+    // Fail with Aborted/Timeout if the running job was cancelled or the step is over its limit
+    fn checkpoint(&self) -> Result<(), InjectionError> {
+        match &self.job {
+            Some(job) => job.check(),
+            None => Ok(()),
+        }
+    }
+    
+    fn begin_step(&mut self, step: InjectionStep) {
+        if let Some(job) = self.job.as_mut() {
+            job.begin_step(step);
+        }
+    }
+    
     fn log(&mut self, message: &str) {
         // Add emoji based on message content
         let emoji_message = if message.contains("Initializing") {
@@ -219,53 +286,6 @@ impl SkinInjector {
         Ok(())
     }
 
-    // Replace the hardcoded get_champion_name with a function that uses JSON data
-    fn get_champion_name(&mut self, champion_id: u32) -> Option<String> {
-        // Check cache first
-        if let Some(name) = self.champion_names.get(&champion_id) {
-            return Some(name.clone());
-        }
-
-        // If not in cache, look up in the champions directory
-        let champions_dir = self.app_dir.join("champions");
-        if !champions_dir.exists() {
-            return None;
-        }
-
-        // Look through all champion directories
-        if let Ok(entries) = fs::read_dir(&champions_dir) {
-            for entry in entries.filter_map(Result::ok) {
-                if !entry.path().is_dir() {
-                    continue;
-                }
-
-                let champion_file = entry.path().join(format!("{}.json", 
-                    entry.file_name().to_string_lossy()));
-
-                if let Ok(content) = fs::read_to_string(&champion_file) {
-                    if let Ok(data) = serde_json::from_str::<serde_json::Value>(&content) {
-                        // Check if this JSON contains the champion ID we're looking for
-                        if let Some(id) = data.get("id").and_then(|v| v.as_u64()) {
-                            if id as u32 == champion_id {
-                                // Found the champion, get their name
-                                if let Some(name) = data.get("name")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_lowercase().replace(" ", "")) 
-                                {
-                                    // Cache it for future lookups
-                                    self.champion_names.insert(champion_id, name.clone());
-                                    return Some(name);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        None
-    }
-    
     // Extract .fantome file (similar to utility::unzip in CSLOL Manager)
     fn extract_fantome(&mut self, fantome_path: &Path, output_dir: &Path) -> Result<(), InjectionError> {
         self.log(&format!("Extracting fantome file: {}", fantome_path.display()));
@@ -278,6 +298,7 @@ impl SkinInjector {
         let mut archive = ZipArchive::new(file)?;
         
         for i in 0..archive.len() {
+            self.checkpoint()?;
             let mut file = archive.by_index(i)?;
             let outpath = match file.enclosed_name() {
                 Some(path) => output_dir.join(path),
@@ -293,7 +314,7 @@ impl SkinInjector {
                     }
                 }
                 let mut outfile = fs::File::create(&outpath)?;
-                io::copy(&mut file, &mut outfile)?;
+                copy_entry(&mut file, &mut outfile, &|| self.checkpoint())?;
             }
         }
         
@@ -320,6 +341,7 @@ impl SkinInjector {
             let mut archive = ZipArchive::new(std::io::Cursor::new(&mmap[..]))?;
             
             for i in 0..archive.len() {
+                self.checkpoint()?;
                 let mut file = archive.by_index(i)?;
                 let outpath = match file.enclosed_name() {
                     Some(path) => output_dir.join(path),
@@ -335,7 +357,7 @@ impl SkinInjector {
                         }
                     }
                     let mut outfile = fs::File::create(&outpath)?;
-                    io::copy(&mut file, &mut outfile)?;
+                    copy_entry(&mut file, &mut outfile, &|| self.checkpoint())?;
                 }
             }
             
@@ -346,6 +368,7 @@ impl SkinInjector {
             let mut archive = ZipArchive::new(file)?;
             
             for i in 0..archive.len() {
+                self.checkpoint()?;
                 let mut file = archive.by_index(i)?;
                 let outpath = match file.enclosed_name() {
                     Some(path) => output_dir.join(path),
@@ -361,7 +384,7 @@ impl SkinInjector {
                         }
                     }
                     let mut outfile = fs::File::create(&outpath)?;
-                    io::copy(&mut file, &mut outfile)?;
+                    copy_entry(&mut file, &mut outfile, &|| self.checkpoint())?;
                 }
             }
         }
@@ -369,6 +392,15 @@ impl SkinInjector {
         Ok(())
     }
     
+    // Validate a mod input (layout, info.json, zip bomb limits) before it is used
+    fn validate_archive(&mut self, fantome_path: &Path) -> Result<(), InjectionError> {
+        let report = validate_mod(fantome_path)?;
+        for warning in &report.warnings {
+            self.log(&format!("WARNING: {}", warning));
+        }
+        report.into_result().map(|_| ())
+    }
+    
     // Check if directory contains META/info.json to confirm it's a valid mod
     fn is_valid_mod_dir(&self, dir_path: &Path) -> bool {
         dir_path.join("META").join("info.json").exists()
@@ -376,205 +408,42 @@ impl SkinInjector {
     
     // Find appropriate .fantome file for a skin
     fn find_fantome_for_skin(&mut self, skin: &Skin, fantome_files_dir: &Path) -> Result<Option<PathBuf>, InjectionError> {
-        // Fall back to the original slow method if index lookup failed
-        self.log("Using fallback file search method");
-        
-        // First try direct path from JSON
-        if let Some(fantome_path) = &skin.fantome_path {
-            self.log(&format!("Using fantome path from JSON: {}", fantome_path));
-            
-            // Try direct path
-            let direct_path = fantome_files_dir.join(fantome_path);
-            if direct_path.exists() {
-                self.log(&format!("Found exact file at path: {}", direct_path.display()));
-                return Ok(Some(direct_path));
-            }
-            
-            // Try path using champion name
-            if let Some(champion_name) = self.get_champion_name(skin.champion_id) {
-                let champ_path = fantome_files_dir.join(champion_name).join(fantome_path.split('/').last().unwrap_or(""));
-                if champ_path.exists() {
-                    self.log(&format!("Found file at champion path: {}", champ_path.display()));
-                    return Ok(Some(champ_path));
-                }
-            }
-            
-            // Search for matching filename
-            let file_name = fantome_path.split('/').last().unwrap_or("");
-            for entry in WalkDir::new(fantome_files_dir) {
-                let entry = entry?;
-                if entry.file_type().is_file() {
-                    let path = entry.path();
-                    if path.file_name()
-                       .map(|name| name.to_string_lossy() == file_name)
-                       .unwrap_or(false) {
-                        self.log(&format!("Found matching file: {}", path.display()));
-                        return Ok(Some(path.to_path_buf()));
-                    }
-                }
-            }
-        }
-        
-        // Fall back to searching by ID
-        self.log(&format!("Searching for skin with champion_id={}, skin_id={}, chroma_id={:?}", 
-            skin.champion_id, skin.skin_id, skin.chroma_id));
-            
-        let skin_id_str = skin.skin_id.to_string();
-        
-        // Try champion directory first
-        if let Some(champion_name) = self.get_champion_name(skin.champion_id) {
-            let champ_dir = fantome_files_dir.join(champion_name);
-            if champ_dir.exists() {
-                for entry in fs::read_dir(champ_dir)? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("fantome") {
-                        continue;
-                    }
-                    
-                    let file_name = path.file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy();
-                        
-                    if file_name.contains(&skin_id_str) {
-                        // Check for chroma
-                        if let Some(chroma_id) = skin.chroma_id {
-                            if file_name.contains("chroma") && file_name.contains(&chroma_id.to_string()) {
-                                self.log(&format!("Found chroma match: {}", path.display()));
-                                return Ok(Some(path.to_path_buf()));
-                            }
-                        } else if !file_name.contains("chroma") {
-                            self.log(&format!("Found non-chroma match: {}", path.display()));
-                            return Ok(Some(path.to_path_buf()));
-                        }
-                    }
-                }
+        let found = self.locate_fantome(skin, fantome_files_dir)?;
+        if found.is_none() {
+            self.set_state(ModState::Idle);
+            // Emit error to frontend
+            if let Some(_app) = &self.app_handle {
+                let error_msg = format!("No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}", skin.champion_id, skin.skin_id, skin.chroma_id);
+                let _ = _app.emit("injection-status", false);
+                let _ = _app.emit("skin-injection-error", error_msg);
             }
         }
-        
-        // Search all files as last resort
-        for entry in WalkDir::new(fantome_files_dir) {
-            let entry = entry?;
-            if entry.file_type().is_file() {
-                let path = entry.path();
-                if path.extension().and_then(|e| e.to_str()) != Some("fantome") {
-                    continue;
-                }
-                
-                let file_name = path.file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy();
-                    
-                if file_name.contains(&skin_id_str) {
-                    // Check for chroma
-                    if let Some(chroma_id) = skin.chroma_id {
-                        if file_name.contains("chroma") && file_name.contains(&chroma_id.to_string()) {
-                            self.log(&format!("Found chroma match in full search: {}", path.display()));
-                            return Ok(Some(path.to_path_buf()));
-                        }
-                    } else if !file_name.contains("chroma") {
-                        self.log(&format!("Found non-chroma match in full search: {}", path.display()));
-                        return Ok(Some(path.to_path_buf()));
-                    }
-                }
-            }
-        }
-        
-        self.log(&format!("No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
-            skin.champion_id, skin.skin_id, skin.chroma_id));
-        self.set_state(ModState::Idle);
-        // Emit error to frontend
-        if let Some(_app) = &self.app_handle {
-            let error_msg = format!("No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}", skin.champion_id, skin.skin_id, skin.chroma_id);
-            let _ = _app.emit("injection-status", false);
-            let _ = _app.emit("skin-injection-error", error_msg);
-        }
-        Ok(None)
+        Ok(found)
     }
     
-    // Create a mod directory structure from extracted fantome files
-    fn create_mod_from_extracted(&mut self, extract_dir: &Path, mod_dir: &Path) -> Result<(), InjectionError> {
-        self.log(&format!("Creating mod from extracted files at: {}", extract_dir.display()));
-        
-        // Create mod directories
-        fs::create_dir_all(mod_dir.join("META"))?;
-        fs::create_dir_all(mod_dir.join("WAD"))?;
-        
-        // Check if there's already a META/info.json in the extracted content
-        let extracted_info_json = extract_dir.join("META").join("info.json");
-        let mod_info_json = mod_dir.join("META").join("info.json");
-        
-        if extracted_info_json.exists() {
-            // Copy the existing info.json
-            fs::copy(&extracted_info_json, &mod_info_json)?;
-        } else {
-            // Create a basic info.json
-            let info_json = format!(r#"{{
-                "Name": "ExtractedMod",
-                "Version": "1.0.0",
-                "Author": "osskins",
-                "Description": "Extracted from fantome file at {}"
-            }}"#, chrono::Local::now().to_rfc3339());
-            
-            fs::write(&mod_info_json, info_json)?;
+    // Search for the .fantome file of a skin without side effects
+    fn locate_fantome(&mut self, skin: &Skin, fantome_files_dir: &Path) -> Result<Option<PathBuf>, InjectionError> {
+        let found = lookup_skin(fantome_files_dir, skin)?;
+        match &found {
+            Some(path) => self.log(&format!("Found mod file for champion_id={}, skin_id={}, chroma_id={:?}: {}",
+                skin.champion_id, skin.skin_id, skin.chroma_id, path.display())),
+            None => self.log(&format!("No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
+                skin.champion_id, skin.skin_id, skin.chroma_id)),
         }
-        
-        // Look for WAD directory in extracted content
-        let extracted_wad_dir = extract_dir.join("WAD");
-        if extracted_wad_dir.exists() {
-            // Copy WAD files
-            for entry in WalkDir::new(&extracted_wad_dir) {
-                let entry = entry?;
-                let path = entry.path();
-                let rel_path = path.strip_prefix(&extracted_wad_dir)
-                    .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?;
-                
-                let target_path = mod_dir.join("WAD").join(rel_path);
-                
-                if path.is_dir() {
-                    fs::create_dir_all(&target_path)?;
-                } else if path.is_file() {
-                    if let Some(parent) = target_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
-                    fs::copy(path, &target_path)?;
-                }
-            }
-        } else {
-            // If no WAD directory, look for WAD files in the root
-            for entry in WalkDir::new(extract_dir) {
-                let entry = entry?;
-                let path = entry.path();
-                
-                // Skip META directory
-                if path.starts_with(extract_dir.join("META")) {
-                    continue;
-                }
-                
-                // Check if this is a WAD file
-                if path.is_file() && 
-                   (path.extension().and_then(|ext| ext.to_str()) == Some("wad") ||
-                    path.to_string_lossy().ends_with(".wad.client")) {
-                    
-                    let file_name = path.file_name().unwrap();
-                    let target_path = mod_dir.join("WAD").join(file_name);
-                    
-                    fs::copy(path, &target_path)?;
-                }
-            }
-        }
-        
-        Ok(())
+        Ok(found)
     }
     
-    // Process .fantome files to create proper mod structure with memory optimization
-    fn process_fantome_file(&mut self, fantome_path: &Path) -> Result<PathBuf, InjectionError> {
-        self.log(&format!("Processing fantome file: {}", fantome_path.display()));
+    // Turn a mod input (.fantome, .zip, bare WAD or folder) into a proper mod structure
+    fn process_mod_file(&mut self, mod_path: &Path) -> Result<PathBuf, InjectionError> {
+        let kind = ModKind::detect(mod_path).ok_or_else(|| InjectionError::InvalidFantome(format!(
+            "Unsupported mod file type: {}", mod_path.display()
+        )))?;
+        self.log(&format!("Processing {:?} mod: {}", kind, mod_path.display()));
         
         // Create temp extraction directory
-        let file_stem = fantome_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
-        let extract_dir = self.app_dir.join("temp").join(&file_stem);
-        let mod_dir = self.app_dir.join("mods").join(&file_stem);
+        let name = mod_name(mod_path);
+        let extract_dir = self.app_dir.join("temp").join(&name);
+        let mod_dir = self.app_dir.join("mods").join(&name);
         
         // Clean up any existing directories
         if extract_dir.exists() {
@@ -584,26 +453,44 @@ impl SkinInjector {
             fs::remove_dir_all(&mod_dir)?;
         }
         
-        // Check file size to decide which extraction method to use
-        let file_size = match fs::metadata(fantome_path) {
-            Ok(metadata) => metadata.len(),
-            Err(_) => 0, // Default to standard extraction if we can't get size
+        let result = match kind {
+            ModKind::Fantome | ModKind::Zip => {
+                // Check file size to decide which extraction method to use
+                let file_size = match fs::metadata(mod_path) {
+                    Ok(metadata) => metadata.len(),
+                    Err(_) => 0, // Default to standard extraction if we can't get size
+                };
+                
+                // Use memory-mapped extraction for larger files
+                if file_size > 1_048_576 { // >1MB
+                    self.extract_fantome_mmap(mod_path, &extract_dir)
+                } else {
+                    // Use standard extraction for smaller files
+                    self.extract_fantome(mod_path, &extract_dir)
+                }
+                // Create mod structure
+                .and_then(|_| {
+                    self.log(&format!("Creating mod from extracted files at: {}", extract_dir.display()));
+                    normalize_folder(&extract_dir, &mod_dir, &name, &|| self.checkpoint())
+                })
+            }
+            ModKind::Wad => {
+                self.log("Creating mod from a bare WAD file");
+                normalize_wad_file(mod_path, &mod_dir, &name)
+            }
+            ModKind::Folder => {
+                self.log("Creating mod from an unpacked folder");
+                normalize_folder(mod_path, &mod_dir, &name, &|| self.checkpoint())
+            }
         };
         
-        // Use memory-mapped extraction for larger files
-        if file_size > 1_048_576 { // >1MB
-            self.extract_fantome_mmap(fantome_path, &extract_dir)?;
-        } else {
-            // Use standard extraction for smaller files
-            self.extract_fantome(fantome_path, &extract_dir)?;
+        // Clean up extraction directory, also when extraction was aborted halfway
+        let _ = fs::remove_dir_all(&extract_dir);
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&mod_dir);
+            return Err(e);
         }
         
-        // Create mod structure
-        self.create_mod_from_extracted(&extract_dir, &mod_dir)?;
-        
-        // Clean up extraction directory
-        fs::remove_dir_all(&extract_dir)?;
-        
         Ok(mod_dir)
     }
     
@@ -653,13 +540,13 @@ impl SkinInjector {
         Ok(())
     }
     
-    // Copy a processed mod directory to the game's mods directory
-    fn copy_mod_to_game(&mut self, mod_dir: &Path) -> Result<(), InjectionError> {
+    // Copy a processed mod directory into a mods directory (usually the staged Game/mods)
+    fn copy_mod_to_game(&mut self, mod_dir: &Path, mods_dir: &Path) -> Result<(), InjectionError> {
         self.log(&format!("Copying mod to game directory: {}", mod_dir.display()));
 
         // Use the mod directory name as the subfolder
         let mod_name = mod_dir.file_name().unwrap();
-        let game_mod_dir = self.game_path.join("mods").join(mod_name);
+        let game_mod_dir = mods_dir.join(mod_name);
 
         // Remove any existing mod with the same name
         if game_mod_dir.exists() {
@@ -669,6 +556,7 @@ impl SkinInjector {
 
         // Copy everything from mod_dir into game_mod_dir
         for entry in WalkDir::new(mod_dir) {
+            self.checkpoint()?;
             let entry = entry?;
             let path = entry.path();
             let rel_path = path.strip_prefix(mod_dir)
@@ -687,69 +575,19 @@ impl SkinInjector {
         Ok(())
     }
     
-    // Run the overlay process using mod-tools.exe
-    fn run_overlay(&mut self) -> Result<(), InjectionError> {
-        let mod_tools_path = match &self.mod_tools_path {
-            Some(path) => {
-                if !path.exists() {
-                    return Err(InjectionError::OverlayError(format!(
-                        "mod-tools.exe was found during initialization but is no longer at path: {}. Please reinstall the application or obtain mod-tools.exe from CSLOL Manager.",
-                        path.display()
-                    )));
-                }
-                path.clone()
-            },
-            None => return Err(InjectionError::OverlayError(
-                "mod-tools.exe not found. Please install CSLOL Manager or copy mod-tools.exe to the application directory.".into()
-            )),
-        };
-
-        self.log(&format!("Using mod-tools.exe from: {}", mod_tools_path.display()));
-
-        // First, ensure no mod-tools processes are running before we start
-        self.cleanup_mod_tools_processes();
+    // Build the overlay from Game/mods into the cache, returns the overlay folder
+    fn prepare_overlay(&mut self, cache: &OverlayCache, cache_key: &str) -> Result<PathBuf, InjectionError> {
+        self.begin_step(InjectionStep::OverlayBuild);
+        
+        // Fail early if we can't run the overlay anyway
+        self.backend.check_available()?;
         
         // Set up directory paths
         let game_mods_dir = self.game_path.join("mods");
-        let overlay_dir = self.app_dir.join("overlay");
-        let temp_overlay_dir = self.app_dir.join("temp_overlay");
-        
-        // Clean up the final overlay directory
-        if overlay_dir.exists() {
-            let mut attempts = 0;
-            let max_attempts = 3;
-            while attempts < max_attempts {
-                match fs::remove_dir_all(&overlay_dir) {
-                    Ok(_) => break,
-                    Err(e) => {
-                        self.log(&format!("Failed to remove overlay directory (attempt {}/{}): {}", 
-                            attempts + 1, max_attempts, e));
-                        std::thread::sleep(std::time::Duration::from_millis(500));
-                        attempts += 1;
-                    }
-                }
-            }
-            if overlay_dir.exists() && attempts >= max_attempts {
-                return Err(InjectionError::OverlayError(
-                    "Cannot remove existing overlay directory. It may be locked by another process.".into()
-                ));
-            }
-        }
+        let staging_dir = cache.staging_dir(cache_key)?;
         
-        // Create the final overlay directory
-        fs::create_dir_all(&overlay_dir)?;
-        
-        // Clean up and recreate the temp overlay directory
-        if temp_overlay_dir.exists() {
-            match fs::remove_dir_all(&temp_overlay_dir) {
-                Ok(_) => {},
-                Err(e) => {
-                    self.log(&format!("Could not clean temp overlay directory: {}", e));
-                    // Try to continue anyway
-                }
-            }
-        }
-        fs::create_dir_all(&temp_overlay_dir)?;
+        // Leftover from the old mkoverlay flow
+        let _ = fs::remove_dir_all(self.app_dir.join("temp_overlay"));
         
         // Get list of mod names (just the directory names, no paths)
         let mut mod_names = Vec::new();
@@ -764,6 +602,8 @@ impl SkinInjector {
                 }
             }
         }
+        mod_names.sort();
+        sort_by_priority(&mut mod_names, &self.mod_priority, |name| name.clone());
         
         // Log the status
         if mod_names.is_empty() {
@@ -772,155 +612,43 @@ impl SkinInjector {
             self.log(&format!("Found {} mods to include in overlay", mod_names.len()));
         }
         
-        // Join mod names with / as CSLOL expects
-        let mods_arg = mod_names.join("/");
-        
-        // Create mod overlay - use more retries and a temporary directory
+        // Build the overlay natively
         self.log("Creating mod overlay...");
-        let max_retries = 5; // Increased from 3 to 5
-        let mut retry_count = 0;
-        let mut last_error = None;
-        
-        while retry_count < max_retries {
-            // Add delays and cleanup between retries
-            if retry_count > 0 {
-                self.log(&format!("Retrying overlay creation (attempt {}/{})", retry_count + 1, max_retries));
-                std::thread::sleep(std::time::Duration::from_millis(1000));
-                self.cleanup_mod_tools_processes();
-                
-                // For additional retries, recreate the temp overlay directory to ensure it's clean
-                if temp_overlay_dir.exists() {
-                    match fs::remove_dir_all(&temp_overlay_dir) {
-                        Ok(_) => fs::create_dir_all(&temp_overlay_dir)?,
-                        Err(e) => {
-                            self.log(&format!("Could not clean temp overlay directory: {}", e));
-                            // Try to continue anyway
-                        }
-                    }
-                } else {
-                    fs::create_dir_all(&temp_overlay_dir)?;
-                }
-                
-                // Run garbage collection to help free memory
-                #[cfg(target_os = "windows")]
-                {
-                    use std::process::Command;
-                    // Run a quick garbage collection via PowerShell to help free memory
-                    if retry_count > 0 {
-                        self.log("Running garbage collection to free memory...");
-                        let mut gc_cmd = Command::new("powershell");
-                        gc_cmd.args(["-Command", "[System.GC]::Collect()"]);
-                        #[cfg(target_os = "windows")]
-                        gc_cmd.creation_flags(CREATE_NO_WINDOW);
-                        let _ = gc_cmd.output(); // Ignore output
-                    }
-                }
-            }
-            
-            // Create the mkoverlay command - using the TEMP directory
-            let mut command = std::process::Command::new(&mod_tools_path);
-            command.args([
-                "mkoverlay",
-                game_mods_dir.to_str().unwrap(),
-                temp_overlay_dir.to_str().unwrap(),  // Use temp directory instead of final directory
-                &format!("--game:{}", self.game_path.to_str().unwrap()),
-                &format!("--mods:{}", mods_arg),
-                "--noTFT",
-                "--ignoreConflict"
-            ]);
-            
-            #[cfg(target_os = "windows")]
-            command.creation_flags(CREATE_NO_WINDOW);
-            
-            if retry_count == 0 {
-                self.log("Running mkoverlay command...");
-            }
-            
-            // Execute the command
-            match command.output() {
-                Ok(output) => {
-                    if output.status.success() {
-                        // Success - copy from temp directory to final overlay directory
-                        self.log("Overlay creation succeeded!");
-                        
-                        // Copy from temp_overlay_dir to overlay_dir
-                        for entry in WalkDir::new(&temp_overlay_dir) {
-                            let entry = entry?;
-                            let path = entry.path();
-                            let rel_path = path.strip_prefix(&temp_overlay_dir)
-                                .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?;
-                            let target_path = overlay_dir.join(rel_path);
-                            
-                            if path.is_dir() {
-                                fs::create_dir_all(&target_path)?;
-                            } else if path.is_file() {
-                                if let Some(parent) = target_path.parent() {
-                                    fs::create_dir_all(parent)?;
-                                }
-                                fs::copy(path, &target_path)?;
-                            }
-                        }
-                        
-                        // Clean up temp directory
-                        let _ = fs::remove_dir_all(&temp_overlay_dir);
-                        
-                        break;
-                    } else {
-                        // Command ran but had error status
-                        let stderr_output = String::from_utf8_lossy(&output.stderr).into_owned();
-                        let stdout_output = String::from_utf8_lossy(&output.stdout).into_owned();
-                        let error_message = if stderr_output.is_empty() { stdout_output } else { stderr_output };
-                        
-                        // Check if it's an access violation error
-                        if output.status.to_string().contains("0xc0000005") {
-                            self.log(&format!("Access violation error in attempt {}/{}. Retrying...", 
-                                retry_count + 1, max_retries));
-                            last_error = Some(InjectionError::ProcessError(format!(
-                                "mkoverlay command failed: {}. Exit code: {}", 
-                                error_message, output.status
-                            )));
-                            retry_count += 1;
-                            continue;
-                        } else {
-                            // Other error, only show error log if this is the final attempt
-                            if retry_count + 1 >= max_retries {
-                                self.log(&format!("Overlay creation failed: {}", error_message));
-                            }
-                            retry_count += 1;
-                            
-                            last_error = Some(InjectionError::ProcessError(format!(
-                                "mkoverlay command failed: {}. Exit code: {}", 
-                                error_message, output.status
-                            )));
-                            
-                            // Try again if we haven't exhausted retries
-                            if retry_count < max_retries {
-                                continue;
-                            }
-                            
-                            // Clean up temp directory
-                            let _ = fs::remove_dir_all(&temp_overlay_dir);
-                            
-                            return Err(last_error.unwrap());
-                        }
-                    }
-                },
-                Err(e) => {
-                    // Command couldn't be started
-                    return Err(InjectionError::ProcessError(format!(
-                        "Failed to create overlay: {}. The mod-tools.exe might be missing or incompatible.", e
-                    )));
-                }
+        let report = match build_overlay(&self.game_path, &game_mods_dir, &mod_names, &staging_dir, true, &|| self.checkpoint()) {
+            Ok(report) => report,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging_dir);
+                return Err(e);
             }
+        };
+        for skipped in &report.mods_skipped {
+            self.log(&format!("Skipped mod without usable WAD entries: {}", skipped));
         }
-        
-        // Check if all retries were exhausted
-        if retry_count >= max_retries {
-            if let Some(err) = last_error {
-                return Err(err);
+        for conflict in &report.conflicts {
+            self.log(&format!(
+                "WARNING: Mods {} override the same {} files, using {}",
+                conflict.mods.join(", "), conflict.paths.len(), conflict.winner
+            ));
+        }
+        if !report.conflicts.is_empty() {
+            if let Some(app) = &self.app_handle {
+                let _ = app.emit("mod-conflicts", &report.conflicts);
             }
-            return Err(InjectionError::ProcessError("Failed to create overlay after multiple attempts".into()));
         }
+        self.log(&format!("Overlay creation succeeded! {} WADs written", report.wads_written));
+        
+        cache.insert(cache_key, &staging_dir)
+    }
+    
+    // Run mod-tools runoverlay on an already built overlay folder
+    fn start_overlay(&mut self, overlay_dir: &Path) -> Result<(), InjectionError> {
+        self.begin_step(InjectionStep::PatcherStart);
+        self.backend.check_available()?;
+        self.log(&format!("Using {}", self.backend.describe()));
+
+        // The previous overlay keeps running until the new one is ready, stop it now
+        self.cleanup_mod_tools_processes();
+        
         // Create config.json
         let config_path = self.app_dir.join("config.json");
         let config_content = r#"{"enableMods":true}"#;
@@ -937,38 +665,28 @@ impl SkinInjector {
         let mut last_run_error = None;
         
         while run_retry_count < max_run_retries {
+            if let Err(e) = self.checkpoint() {
+                self.set_state(ModState::Idle);
+                return Err(e);
+            }
             if run_retry_count > 0 {
                 self.log(&format!("Retrying overlay run (attempt {}/{})", run_retry_count + 1, max_run_retries));
                 std::thread::sleep(std::time::Duration::from_millis(1000));
                 self.cleanup_mod_tools_processes();
-                
-                // Additional cleanup - run garbage collection to free memory
-                #[cfg(target_os = "windows")]
-                {
-                    use std::process::Command;
-                    let mut gc_cmd = Command::new("powershell");
-                    gc_cmd.args(["-Command", "[System.GC]::Collect()"]);
-                    #[cfg(target_os = "windows")]
-                    gc_cmd.creation_flags(CREATE_NO_WINDOW);
-                    let _ = gc_cmd.output();
-                }
             }
             
-            // Run the overlay process - EXACT format from CSLOL
-            let mut command = std::process::Command::new(&mod_tools_path);
-            command.args([
-                "runoverlay",
-                overlay_dir.to_str().unwrap(),
-                config_path.to_str().unwrap(),
-                &format!("--game:{}", self.game_path.to_str().unwrap()),
-                "--opts:configless"
-            ]);
+            let make_command = self.backend.runoverlay_command(overlay_dir, &config_path, &self.game_path)?;
             
-            #[cfg(target_os = "windows")]
-            command.creation_flags(CREATE_NO_WINDOW);
-            
-            match command.spawn() {
-                Ok(_) => {
+            // The supervisor owns the child from here on and restarts it if it dies
+            match PatcherSupervisor::start(make_command, self.app_handle.clone()) {
+                Ok(supervisor) => {
+                    // Cancelled while spawning, don't leave the new patcher running
+                    if let Err(e) = self.checkpoint() {
+                        supervisor.stop();
+                        self.set_state(ModState::Idle);
+                        return Err(e);
+                    }
+                    set_active_patcher(supervisor);
                     self.log("Overlay process started successfully");
                     
                     // Emit success to frontend if available
@@ -985,8 +703,8 @@ impl SkinInjector {
                     run_retry_count += 1;
                     last_run_error = Some(match e.kind() {
                         io::ErrorKind::NotFound => InjectionError::OverlayError(format!(
-                            "mod-tools.exe not found or is inaccessible at path: {}. Please install CSLOL Manager or copy the correct mod-tools.exe to the application directory.", 
-                            mod_tools_path.display()
+                            "{} not found or is inaccessible. Please install CSLOL Manager or copy the correct mod-tools.exe to the application directory.", 
+                            self.backend.describe()
                         )),
                         io::ErrorKind::PermissionDenied => InjectionError::OverlayError(format!(
                             "Permission denied when trying to run mod-tools.exe. Try running the application as administrator."
@@ -1017,119 +735,158 @@ impl SkinInjector {
     
     // Helper function to kill mod-tools processes - extracted from cleanup for reuse
     fn cleanup_mod_tools_processes(&self) {
-        #[cfg(target_os = "windows")]
-        {
-            // First try normal taskkill
-            let mut command = std::process::Command::new("taskkill");
-            command.args(["/F", "/IM", "mod-tools.exe"]);
-            
-            #[cfg(target_os = "windows")]
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            #[cfg(target_os = "windows")]
-            command.creation_flags(CREATE_NO_WINDOW);
-            
-            let _ = command.output();
-            
-            // Then check if any processes are still running with wmic (more reliable)
-            let mut check_command = std::process::Command::new("wmic");
-            check_command.args(["process", "where", "name='mod-tools.exe'", "get", "processid"]);
-            #[cfg(target_os = "windows")]
-            check_command.creation_flags(CREATE_NO_WINDOW);
-            
-            // If we find any processes still running, use taskkill with /PID for each one
-            if let Ok(output) = check_command.output() {
-                if output.status.success() {
-                    let output_str = String::from_utf8_lossy(&output.stdout);
-                    for line in output_str.lines() {
-                        let line = line.trim();
-                        if line != "ProcessId" && !line.is_empty() && line.chars().all(|c| c.is_digit(10)) {
-                            // Found a PID, kill it specifically
-                            let mut kill_pid = std::process::Command::new("taskkill");
-                            kill_pid.args(["/F", "/PID", line]);
-                            #[cfg(target_os = "windows")]
-                            kill_pid.creation_flags(CREATE_NO_WINDOW);
-                            let _ = kill_pid.output();
-                        }
-                    }
-                }
-            }
-        }
+        // Stop the patcher we started ourselves first, this lets it exit cleanly
+        stop_active_patcher();
+        self.backend.kill_stray_processes();
     }
 
-    // Main injection method that does all steps
+    // Main injection method that does all steps, as a job that cancel_injection can abort
     pub fn inject_skins(&mut self, skins: &[Skin], fantome_files_dir: &Path) -> Result<(), InjectionError> {
+        self.job = Some(InjectionJob::start(self.timeouts));
+        let result = self.run_injection(skins, fantome_files_dir);
+        self.job = None;
+        
+        if let Err(InjectionError::Aborted(msg)) = &result {
+            self.log(&format!("Skin injection cancelled: {}", msg));
+            if let Some(app) = &self.app_handle {
+                let _ = app.emit("injection-status", "cancelled");
+            }
+        }
+        result
+    }
+    
+    fn run_injection(&mut self, skins: &[Skin], fantome_files_dir: &Path) -> Result<(), InjectionError> {
         // Emit start event to frontend
         if let Some(_app) = &self.app_handle {
             let _ = _app.emit("injection-status", "injecting");
         }
 
-        // First, ensure that we clean up any existing running processes
-        // We do this even if we're not in Running state to avoid issues with orphaned processes
-        self.cleanup()?;
-        
-        // Now we can properly initialize for a new injection
+        // Any running overlay is left alone until the new one is ready to start
         self.set_state(ModState::Busy);
         self.log("Starting skin injection process...");
         
-        // First, clean up the game's mods directory
-        let game_mods_dir = self.game_path.join("mods");
-        if game_mods_dir.exists() {
-            self.log("Cleaning up existing mods in game directory");
-            fs::remove_dir_all(&game_mods_dir)?;
+        // Resolve all fantome files up front so the overlay cache key can be computed
+        let mut fantome_paths = Vec::with_capacity(skins.len());
+        for skin in skins {
+            if let Err(e) = self.checkpoint() {
+                self.set_state(ModState::Idle);
+                return Err(e);
+            }
+            match self.find_fantome_for_skin(skin, fantome_files_dir)? {
+                Some(fantome_path) => {
+                    // Nothing reaches Game/mods unless every archive passes validation
+                    if let Err(e) = self.validate_archive(&fantome_path) {
+                        self.log(&format!("ERROR: {}", e));
+                        self.set_state(ModState::Idle);
+                        return Err(e);
+                    }
+                    fantome_paths.push(fantome_path);
+                }
+                None => {
+                    let msg = format!(
+                        "No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
+                        skin.champion_id, skin.skin_id, skin.chroma_id
+                    );
+                    self.log(&format!("ERROR: {}", msg));
+                    self.set_state(ModState::Idle);
+                    return Err(InjectionError::MissingFantomeFile(msg));
+                }
+            }
+        }
+        
+        // Reuse a previously built overlay for the exact same loadout and game version
+        // Mods are hashed in build order since priority changes the result
+        let mut ordered_paths = fantome_paths.clone();
+        ordered_paths.sort();
+        sort_by_priority(&mut ordered_paths, &self.mod_priority, |path| {
+            mod_name(path)
+        });
+        let cache = OverlayCache::new(self.app_dir.join("overlay_cache"));
+        let cache_key = cache.compute_key(&ordered_paths, &self.game_path)?;
+        if let Err(e) = self.checkpoint() {
+            self.set_state(ModState::Idle);
+            return Err(e);
         }
-        fs::create_dir_all(&game_mods_dir)?;
+        let previous_overlay = cache.active();
+        if let Some(cached_dir) = cache.lookup(&cache_key) {
+            self.log(&format!("Reusing cached overlay {}", cache_key));
+            if let Err(e) = self.enable_mods_in_game_cfg() {
+                self.log(&format!("ERROR: {}", e));
+                self.set_state(ModState::Idle);
+                return Err(e);
+            }
+            if let Err(e) = self.start_overlay(&cached_dir) {
+                self.restore_overlay(previous_overlay.as_deref(), &cached_dir);
+                return Err(e);
+            }
+            cache.set_active(&cache_key);
+            
+            self.log("Skin injection completed successfully");
+            if let Some(_app) = &self.app_handle {
+                let _ = _app.emit("injection-status", "completed");
+            }
+            return Ok(());
+        }
+        
+        // Resolving and hashing is done, extraction gets its full time limit
+        self.begin_step(InjectionStep::Extraction);
+        
+        // Stage the new mods next to Game/mods so a failure leaves the current ones untouched
+        let mut transaction = ModsTransaction::begin(&self.game_path)?;
         
         // Process each skin
-        for (i, skin) in skins.iter().enumerate() {
+        for (i, (skin, fantome_path)) in skins.iter().zip(&fantome_paths).enumerate() {
             self.log(&format!("Processing skin {}/{}: champion_id={}, skin_id={}, chroma_id={:?}", 
                 i + 1, skins.len(), skin.champion_id, skin.skin_id, skin.chroma_id));
-                
-            // Find the fantome file
-            let fantome_path = self.find_fantome_for_skin(skin, fantome_files_dir)?;
-            if let Some(fantome_path) = fantome_path {
-                self.log(&format!("Found fantome file: {}", fantome_path.display()));
-                
-                // Process the fantome file to create a proper mod structure
-                let mod_dir = self.process_fantome_file(&fantome_path)?;
-                
-                // Copy the processed mod to the game
+            self.log(&format!("Found fantome file: {}", fantome_path.display()));
+            
+            // Process the fantome file to create a proper mod structure
+            // Returning early drops the transaction, which discards the staged mods
+            let staged = self.process_mod_file(fantome_path).and_then(|mod_dir| {
+                // Copy the processed mod to the staged mods directory
                 if self.is_valid_mod_dir(&mod_dir) {
                     self.log("Mod structure is valid, copying to game directory");
-                    self.copy_mod_to_game(&mod_dir)?;
+                    self.copy_mod_to_game(&mod_dir, transaction.staging_dir())
                 } else {
                     self.log("ERROR: Processing failed, mod structure invalid");
-                    return Err(InjectionError::MissingFantomeFile("Mod structure invalid".into()));
+                    Err(InjectionError::MissingFantomeFile("Mod structure invalid".into()))
                 }
-            } else {
-                let msg = format!(
-                    "No fantome file found for skin: champion_id={}, skin_id={}, chroma_id={:?}",
-                    skin.champion_id, skin.skin_id, skin.chroma_id
-                );
-                self.log(&format!("ERROR: {}", msg));
+            });
+            if let Err(e) = staged {
                 self.set_state(ModState::Idle);
-                return Err(InjectionError::MissingFantomeFile(msg));
+                return Err(e);
             }
         }
         
-        // Enable mods in Game.cfg
-        self.enable_mods_in_game_cfg()?;
+        // Every mod is ready, swap them in
+        self.log("All mods processed, replacing the game's mods directory");
+        transaction.swap()?;
         
-        // Get the list of mod names we've installed
-        let mut mod_names = Vec::new();
-        for entry in fs::read_dir(&game_mods_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() && path.join("META").join("info.json").exists() {
-                if let Some(name) = path.file_name() {
-                    if let Some(name_str) = name.to_str() {
-                        mod_names.push(name_str.to_string());
-                    }
+        // Enable mods in Game.cfg and build the overlay - THIS is the key part that makes skins actually show in-game!
+        let overlay_dir = match self.enable_mods_in_game_cfg()
+            .and_then(|_| self.prepare_overlay(&cache, &cache_key))
+        {
+            Ok(dir) => dir,
+            Err(e) => {
+                self.log(&format!("ERROR: {}. Restoring previous mods", e));
+                if let Err(rollback_err) = transaction.rollback() {
+                    self.log(&format!("Failed to restore previous mods: {}", rollback_err));
                 }
+                self.set_state(ModState::Idle);
+                return Err(e);
             }
-        }
+        };
         
-        // Start the overlay process - THIS is the key part that makes skins actually show in-game!
-        self.run_overlay()?;
+        if let Err(e) = self.start_overlay(&overlay_dir) {
+            self.log(&format!("ERROR: {}. Restoring previous mods", e));
+            if let Err(rollback_err) = transaction.rollback() {
+                self.log(&format!("Failed to restore previous mods: {}", rollback_err));
+            }
+            self.restore_overlay(previous_overlay.as_deref(), &overlay_dir);
+            return Err(e);
+        }
+        transaction.commit()?;
+        cache.set_active(&cache_key);
         
         self.log("Skin injection completed successfully");
         // Note: We don't set state to Idle because we're now in Running state with the overlay active
@@ -1139,51 +896,108 @@ impl SkinInjector {
         }
         Ok(())
     }
+    
+    // Bring back the overlay that was running before a failed injection
+    fn restore_overlay(&mut self, previous: Option<&Path>, failed: &Path) {
+        // The job is over at this point, restoring must not be cancelled or time out
+        self.job = None;
+        match previous {
+            Some(previous) if previous != failed => {
+                self.log("Restarting the previous overlay");
+                if let Err(e) = self.start_overlay(previous) {
+                    self.log(&format!("Failed to restart the previous overlay: {}", e));
+                }
+            }
+            _ => self.set_state(ModState::Idle),
+        }
+    }
+
+    // Dry run of inject_skins: resolve and validate every mod without touching Game/mods
+    pub fn plan_injection(&mut self, skins: &[Skin], fantome_files_dir: &Path) -> Result<InjectionPlan, InjectionError> {
+        self.log(&format!("Planning injection of {} skins", skins.len()));
+        
+        let mut mods = Vec::new();
+        let mut missing = Vec::new();
+        let mut indexes = Vec::new();
+        let mut fantome_paths = Vec::new();
+        for skin in skins {
+            let fantome_path = match self.locate_fantome(skin, fantome_files_dir)? {
+                Some(path) => path,
+                None => {
+                    missing.push(skin.clone());
+                    continue;
+                }
+            };
+            
+            let mut planned = PlannedMod {
+                champion_id: skin.champion_id,
+                skin_id: skin.skin_id,
+                chroma_id: skin.chroma_id,
+                mod_name: mod_name(&fantome_path),
+                fantome_path: fantome_path.to_string_lossy().to_string(),
+                file_size: fs::metadata(&fantome_path).map(|m| m.len()).unwrap_or(0),
+                wad_count: 0,
+                entry_count: 0,
+                error: None,
+            };
+            match self.validate_archive(&fantome_path)
+                .and_then(|_| WadIndex::from_mod_path(&fantome_path))
+            {
+                Ok(index) => {
+                    planned.wad_count = index.mounts.len();
+                    planned.entry_count = index.entry_count();
+                    indexes.push(index);
+                }
+                Err(e) => {
+                    self.log(&format!("Mod {} is not usable: {}", planned.mod_name, e));
+                    planned.error = Some(e.to_string());
+                }
+            }
+            fantome_paths.push(fantome_path);
+            mods.push(planned);
+        }
+        
+        // Same ordering the overlay build uses, so the reported winner is the real one
+        indexes.sort_by(|a, b| a.name.cmp(&b.name));
+        sort_by_priority(&mut indexes, &self.mod_priority, |index| index.name.clone());
+        let conflicts = find_conflicts(&indexes);
+        
+        let can_inject = missing.is_empty() && mods.iter().all(|m| m.error.is_none());
+        let cached = if can_inject {
+            fantome_paths.sort();
+            sort_by_priority(&mut fantome_paths, &self.mod_priority, |path| {
+                mod_name(path)
+            });
+            let cache = OverlayCache::new(self.app_dir.join("overlay_cache"));
+            cache.compute_key(&fantome_paths, &self.game_path)
+                .map(|key| cache.contains(&key))
+                .unwrap_or(false)
+        } else {
+            false
+        };
+        
+        Ok(InjectionPlan {
+            total_size: mods.iter().map(|m| m.file_size).sum(),
+            mods,
+            missing,
+            conflicts,
+            cached,
+            can_inject,
+        })
+    }
 
     // Add a cleanup method to stop the injection
     pub fn cleanup(&mut self) -> Result<(), InjectionError> {
         self.log("Stopping skin injection process...");
         
-        // Find and kill the mod-tools processes - more aggressive approach
-        #[cfg(target_os = "windows")]
-        {
-            // First try normal taskkill
-            let mut command = std::process::Command::new("taskkill");
-            command.args(["/F", "/IM", "mod-tools.exe"]);
-            
-            #[cfg(target_os = "windows")]
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            #[cfg(target_os = "windows")]
-            command.creation_flags(CREATE_NO_WINDOW);
-            
-            let _ = command.output();
-            
-            // Then check if any processes are still running with wmic (more reliable)
-            let mut check_command = std::process::Command::new("wmic");
-            check_command.args(["process", "where", "name='mod-tools.exe'", "get", "processid"]);
-            #[cfg(target_os = "windows")]
-            check_command.creation_flags(CREATE_NO_WINDOW);
-            
-            // If we find any processes still running, use taskkill with /PID for each one
-            if let Ok(output) = check_command.output() {
-                if output.status.success() {
-                    let output_str = String::from_utf8_lossy(&output.stdout);
-                    for line in output_str.lines() {
-                        let line = line.trim();
-                        if line != "ProcessId" && !line.is_empty() && line.chars().all(|c| c.is_digit(10)) {
-                            // Found a PID, kill it specifically
-                            let mut kill_pid = std::process::Command::new("taskkill");
-                            kill_pid.args(["/F", "/PID", line]);
-                            #[cfg(target_os = "windows")]
-                            kill_pid.creation_flags(CREATE_NO_WINDOW);
-                            let _ = kill_pid.output();
-                        }
-                    }
-                }
-            }
+        if stop_active_patcher() {
+            self.log("Stopped the running overlay process");
         }
         
-        // Clean up the overlay directory
+        // Find and kill any other mod-tools processes
+        self.backend.kill_stray_processes();
+        
+        // Clean up the overlay directory left by older versions (overlays now live in overlay_cache)
         let overlay_dir = self.app_dir.join("overlay");
         if overlay_dir.exists() {
             // Try multiple times if needed - sometimes Windows file locks take time to release
@@ -1207,27 +1021,48 @@ impl SkinInjector {
 }
 
 // Main wrapper function that is called from commands.rs
+// Goes through the coordinator so manual and automatic injections never overlap.
+// Errors keep their kind so callers can tell a cancelled injection from a failed one.
 pub fn inject_skins(
     app_handle: &AppHandle, 
+    source: &str,
     game_path: &str, 
     skins: &[Skin], 
-    fantome_files_dir: &Path
-) -> Result<(), String> {
-    // Create injector
-    let mut injector = SkinInjector::new(app_handle, game_path)
+    fantome_files_dir: &Path,
+    mod_priority: &[String],
+    timeouts: InjectionTimeouts
+) -> Result<InjectionOutcome, InjectionError> {
+    coordinator::submit(source, game_path, skins, mod_priority, || {
+        // Create injector
+        let mut injector = SkinInjector::new(app_handle, game_path)?;
+        injector.set_mod_priority(mod_priority.to_vec());
+        injector.set_timeouts(timeouts);
+        
+        // Initialize
+        injector.initialize()?;
+        
+        // Inject skins
+        injector.inject_skins(skins, fantome_files_dir)
+    })
+}
+
+// Dry-run wrapper used by the plan_injection command
+pub fn plan_injection(
+    app_handle: &AppHandle,
+    game_path: &str,
+    skins: &[Skin],
+    fantome_files_dir: &Path,
+    mod_priority: &[String]
+) -> Result<InjectionPlan, String> {
+    let mut injector = SkinInjector::for_planning(app_handle, game_path)
         .map_err(|e| format!("Failed to create injector: {}", e))?;
+    injector.set_mod_priority(mod_priority.to_vec());
     
-    // Initialize
-    injector.initialize()
-        .map_err(|e| format!("Failed to initialize: {}", e))?;
-    
-    // Inject skins
-    injector.inject_skins(skins, fantome_files_dir)
-        .map_err(|e| format!("Failed to inject skins: {}", e))
+    injector.plan_injection(skins, fantome_files_dir)
+        .map_err(|e| format!("Failed to plan injection: {}", e))
 }
 
 // New function to clean up the injection when needed
-#[allow(dead_code)]
 pub fn cleanup_injection(
     app_handle: &AppHandle,
     game_path: &str
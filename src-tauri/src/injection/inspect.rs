@@ -0,0 +1,77 @@
+use std::path::Path;
+use serde::Serialize;
+
+use crate::injection::error::InjectionError;
+use crate::injection::hashes::HashTable;
+use crate::injection::normalize::{mod_name, ModKind};
+use crate::injection::overlay::WadIndex;
+use crate::injection::validation::{validate_mod, FantomeInfo};
+
+// Entry listing of a mod for the inspect_mod command
+// The input is validated first (same limits as an upload), then its WADs are read
+// the way the overlay builder reads them, without extracting anything to disk.
+// Entry paths come from the mod itself or, when given, the WAD hash dictionary.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WadEntryInfo {
+    pub hash: String, // 16 hex digits, u64 doesn't survive JSON numbers
+    pub path: Option<String>, // Known for loose files or when the dictionary has the hash
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub compression: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WadContents {
+    pub wad: String,   // e.g. "WAD/Ahri.wad.client"
+    pub mount: String, // Game WAD it replaces, e.g. "ahri"
+    pub entries: Vec<WadEntryInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModInspection {
+    pub path: String,
+    pub name: String,
+    pub kind: Option<ModKind>,
+    pub info: Option<FantomeInfo>,
+    pub wads: Vec<WadContents>,
+    pub entry_count: usize,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub warnings: Vec<String>,
+}
+
+pub fn inspect_mod(path: &Path, hashes: Option<&HashTable>) -> Result<ModInspection, InjectionError> {
+    let report = validate_mod(path)?.into_result()?;
+    let index = WadIndex::from_mod_path(path)?;
+
+    let wads: Vec<WadContents> = index.mounts.values()
+        .map(|mounted| WadContents {
+            wad: mounted.relpath.clone(),
+            mount: mounted.mount_name(),
+            entries: mounted.archive.entries.iter()
+                .map(|(name, entry)| WadEntryInfo {
+                    hash: format!("{:016x}", name),
+                    path: mounted.archive.names.get(name).cloned()
+                        .or_else(|| hashes.and_then(|table| table.resolve(*name)).map(String::from)),
+                    compressed_size: entry.data.len() as u64,
+                    uncompressed_size: entry.size_decompressed,
+                    compression: entry.entry_type.name(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    let entries = wads.iter().flat_map(|wad| wad.entries.iter());
+    Ok(ModInspection {
+        path: path.display().to_string(),
+        name: mod_name(path),
+        kind: report.kind,
+        info: report.info,
+        entry_count: index.entry_count(),
+        compressed_size: entries.clone().map(|e| e.compressed_size).sum(),
+        uncompressed_size: entries.map(|e| e.uncompressed_size).sum(),
+        wads,
+        warnings: report.warnings,
+    })
+}
@@ -0,0 +1,111 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+
+use crate::injection::error::InjectionError;
+use crate::injection::types::InjectionTimeouts;
+
+// Cancellable injection job
+// The injector calls check() between units of work (zip entries, mods, WADs, spawn
+// attempts). It fails with Aborted once cancel_current_job() was called and with
+// Timeout once the current step ran longer than its configured limit.
+
+// The job that is currently running, if any: its id and cancel flag
+type RunningJob = (u64, Arc<AtomicBool>);
+static CURRENT_JOB: Lazy<Mutex<Option<RunningJob>>> = Lazy::new(|| Mutex::new(None));
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectionStep {
+    Extraction,
+    OverlayBuild,
+    PatcherStart,
+}
+
+impl InjectionStep {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Extraction => "extraction",
+            Self::OverlayBuild => "overlay build",
+            Self::PatcherStart => "overlay process start",
+        }
+    }
+
+    fn timeout(self, timeouts: &InjectionTimeouts) -> Duration {
+        Duration::from_secs(match self {
+            Self::Extraction => timeouts.extraction_secs,
+            Self::OverlayBuild => timeouts.overlay_build_secs,
+            Self::PatcherStart => timeouts.patcher_start_secs,
+        })
+    }
+}
+
+pub struct InjectionJob {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    timeouts: InjectionTimeouts,
+    step: InjectionStep,
+    step_started: Instant,
+}
+
+impl InjectionJob {
+    // Register a new job as the current one, cancelling any job still running
+    pub fn start(timeouts: InjectionTimeouts) -> Self {
+        let id = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let previous = CURRENT_JOB.lock().unwrap().replace((id, cancelled.clone()));
+        if let Some((_, previous)) = previous {
+            previous.store(true, Ordering::SeqCst);
+        }
+        Self {
+            id,
+            cancelled,
+            timeouts,
+            step: InjectionStep::Extraction,
+            step_started: Instant::now(),
+        }
+    }
+
+    // Start the deadline for the next step
+    pub fn begin_step(&mut self, step: InjectionStep) {
+        println!("Injection step: {} (limit {:?})", step.name(), step.timeout(&self.timeouts));
+        self.step = step;
+        self.step_started = Instant::now();
+    }
+
+    pub fn check(&self) -> Result<(), InjectionError> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            return Err(InjectionError::Aborted(format!(
+                "Injection was cancelled during {}", self.step.name()
+            )));
+        }
+        let limit = self.step.timeout(&self.timeouts);
+        if self.step_started.elapsed() > limit {
+            return Err(InjectionError::Timeout(format!(
+                "{} took longer than {} seconds", self.step.name(), limit.as_secs()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for InjectionJob {
+    fn drop(&mut self) {
+        let mut current = CURRENT_JOB.lock().unwrap();
+        if matches!(current.as_ref(), Some((id, _)) if *id == self.id) {
+            *current = None;
+        }
+    }
+}
+
+// Ask the running injection to stop. Returns false when nothing is running.
+pub fn cancel_current_job() -> bool {
+    match CURRENT_JOB.lock().unwrap().as_ref() {
+        Some((_, cancelled)) => {
+            cancelled.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
@@ -1,13 +1,42 @@
 // Main injection module that re-exports all components
+mod backend;
 mod cache;
+mod coordinator;
+mod detect;
 mod error;
+mod hashes;
+#[cfg(feature = "test-support")]
+pub mod fixtures;
 mod injector;
+mod inspect;
+mod job;
+mod normalize;
+mod overlay;
+mod patcher;
+mod preview;
+mod transaction;
 mod types;
 mod utils;
+mod validation;
+mod wad;
 
 pub use injector::SkinInjector;
+pub use backend::ModToolsBackend;
+#[cfg(feature = "test-support")]
+pub use backend::fake::FakeModTools;
 pub use types::*;
 pub use utils::*;
 
 // Re-export the main public functions directly
-pub use injector::inject_skins;
\ No newline at end of file
+pub use injector::{cleanup_injection, inject_skins, plan_injection};
+pub use error::InjectionError;
+pub use job::cancel_current_job;
+pub use patcher::set_game_active;
+pub use validation::{extract_zip, validate_mod, zip_contains_mods};
+pub use inspect::{inspect_mod, ModInspection};
+pub use cache::hash_file;
+pub use detect::detect_champion;
+pub use preview::write_preview;
+pub use normalize::{copy_tree, find_mods, is_mod_folder, mod_name, ModKind};
+pub use hashes::{hash_table, is_hash_file, preload_hash_table, reload_hash_table};
+pub use coordinator::{snapshot as injection_state, InjectionOutcome, InjectionStateSnapshot};
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::injection::error::InjectionError;
+
+// Input normalizer: every accepted mod file type becomes the cslol mod layout
+//   <mod>/META/info.json
+//   <mod>/WAD/<name>.wad.client  (file or folder of loose files)
+//   <mod>/RAW/...                (optional)
+// .fantome and plain .zip archives are extracted first, bare WADs are wrapped and
+// unpacked folders are copied. info.json is generated when the input has none.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModKind {
+    Fantome,
+    Zip,
+    Wad,
+    Folder,
+}
+
+impl ModKind {
+    pub fn detect(path: &Path) -> Option<Self> {
+        if path.is_dir() {
+            return Some(Self::Folder);
+        }
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".fantome") {
+            Some(Self::Fantome)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if is_wad_name(&name) || name.ends_with(".client") {
+            Some(Self::Wad)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_wad_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with(".wad") || name.ends_with(".wad.client")
+}
+
+// Name of the mod folder for an input, without any mod extension
+// (Ahri_Arcade.fantome, Ahri_Arcade.zip and Ahri_Arcade.wad.client all give Ahri_Arcade)
+pub fn mod_name(path: &Path) -> String {
+    let name = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let lower = name.to_lowercase();
+    for ext in [".fantome", ".zip", ".wad.client", ".wad", ".client"] {
+        if lower.ends_with(ext) && lower.len() > ext.len() {
+            return name[..name.len() - ext.len()].to_string();
+        }
+    }
+    name
+}
+
+// WADs of an unpacked mod: WAD files and WAD folders below dir, outside META/ and RAW/
+// Returns (WAD file name, path) pairs
+pub fn find_wads(dir: &Path) -> Result<Vec<(String, PathBuf)>, InjectionError> {
+    let dir_name = dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // The folder itself is a WAD folder, e.g. Ahri.wad.client/assets/...
+    if is_wad_name(&dir_name) {
+        return Ok(vec![(dir_name, dir.to_path_buf())]);
+    }
+
+    let mut wads = Vec::new();
+    let mut walker = WalkDir::new(dir).min_depth(1).into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry.depth() == 1 && entry.file_type().is_dir()
+            && (file_name.eq_ignore_ascii_case("META") || file_name.eq_ignore_ascii_case("RAW"))
+        {
+            walker.skip_current_dir();
+            continue;
+        }
+        if !is_wad_name(&file_name) {
+            continue;
+        }
+        if entry.file_type().is_dir() {
+            walker.skip_current_dir();
+            wads.push((file_name, entry.into_path()));
+        } else if entry.file_type().is_file() {
+            wads.push((file_name, entry.into_path()));
+        }
+    }
+    wads.sort();
+    Ok(wads)
+}
+
+pub fn write_default_info(mod_dir: &Path, name: &str) -> Result<(), InjectionError> {
+    let info = serde_json::json!({
+        "Name": name,
+        "Author": "Unknown",
+        "Version": "1.0.0",
+        "Description": format!("Imported by osskins on {}", chrono::Local::now().format("%Y-%m-%d")),
+    });
+    fs::create_dir_all(mod_dir.join("META"))?;
+    fs::write(mod_dir.join("META").join("info.json"), info.to_string())?;
+    Ok(())
+}
+
+pub fn copy_tree(
+    src: &Path,
+    dst: &Path,
+    checkpoint: &dyn Fn() -> Result<(), InjectionError>,
+) -> Result<(), InjectionError> {
+    for entry in WalkDir::new(src) {
+        checkpoint()?;
+        let entry = entry?;
+        let rel_path = entry.path().strip_prefix(src)
+            .map_err(|e| InjectionError::ProcessError(format!("Path error: {}", e)))?;
+        let target = dst.join(rel_path);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// Whether a folder is a mod on its own rather than a folder holding mods
+pub fn is_mod_folder(dir: &Path) -> bool {
+    let name = dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    is_wad_name(&name) || dir.join("META").join("info.json").is_file() || dir.join("WAD").is_dir()
+}
+
+// Every mod in a library folder: mod files at any depth and folders that are mods themselves
+pub fn find_mods(dir: &Path) -> Result<Vec<PathBuf>, InjectionError> {
+    let mut mods = Vec::new();
+    let mut walker = WalkDir::new(dir).min_depth(1).sort_by_file_name().into_iter();
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        if entry.file_type().is_dir() {
+            if is_mod_folder(entry.path()) {
+                walker.skip_current_dir();
+                mods.push(entry.into_path());
+            }
+        } else if entry.file_type().is_file() && ModKind::detect(entry.path()).is_some() {
+            mods.push(entry.into_path());
+        }
+    }
+    Ok(mods)
+}
+
+// Build mod_dir from an extracted archive or an unpacked mod folder
+pub fn normalize_folder(
+    src: &Path,
+    mod_dir: &Path,
+    name: &str,
+    checkpoint: &dyn Fn() -> Result<(), InjectionError>,
+) -> Result<(), InjectionError> {
+    let wads = find_wads(src)?;
+    let raw_dir = src.join("RAW");
+    if wads.is_empty() && !raw_dir.is_dir() {
+        return Err(InjectionError::InvalidFantome(format!("No WAD files found in {}", src.display())));
+    }
+
+    fs::create_dir_all(mod_dir.join("WAD"))?;
+    let meta_dir = src.join("META");
+    if meta_dir.is_dir() {
+        copy_tree(&meta_dir, &mod_dir.join("META"), checkpoint)?;
+    }
+    if !mod_dir.join("META").join("info.json").exists() {
+        write_default_info(mod_dir, name)?;
+    }
+
+    for (file_name, path) in wads {
+        let target = mod_dir.join("WAD").join(&file_name);
+        if path.is_dir() {
+            copy_tree(&path, &target, checkpoint)?;
+        } else {
+            checkpoint()?;
+            fs::copy(&path, &target)?;
+        }
+    }
+    if raw_dir.is_dir() {
+        copy_tree(&raw_dir, &mod_dir.join("RAW"), checkpoint)?;
+    }
+    Ok(())
+}
+
+// Wrap a bare WAD file into mod_dir
+pub fn normalize_wad_file(src: &Path, mod_dir: &Path, name: &str) -> Result<(), InjectionError> {
+    let file_name = src.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    // "Ahri.client" and the like still have to end up as a .wad.client
+    let file_name = if is_wad_name(&file_name) {
+        file_name
+    } else {
+        format!("{}.wad.client", name)
+    };
+
+    fs::create_dir_all(mod_dir.join("WAD"))?;
+    write_default_info(mod_dir, name)?;
+    fs::copy(src, mod_dir.join("WAD").join(file_name))?;
+    Ok(())
+}
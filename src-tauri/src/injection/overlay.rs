@@ -0,0 +1,569 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::io::Read;
+use zip::ZipArchive;
+use walkdir::WalkDir;
+
+use crate::injection::error::InjectionError;
+use crate::injection::normalize::{self, is_wad_name, ModKind};
+use crate::injection::types::ModConflict;
+use crate::injection::wad::{self, WadArchive};
+
+// Native replacement for `mod-tools mkoverlay`
+// Mirrors cslol-tools' lol::wad::Index / Mounted and the mkoverlay flow in main_mod_tools.cpp
+
+// Maps that TFT mods can not touch, skipped like mkoverlay --noTFT
+const TFT_MOUNTS: [&str; 2] = ["map21", "map22"];
+
+// A WAD mounted at a path relative to the game folder
+#[derive(Debug, Clone)]
+pub struct MountedWad {
+    pub relpath: String,
+    pub archive: WadArchive,
+}
+
+impl MountedWad {
+    pub fn mount_name(&self) -> String {
+        wad::mount_name(Path::new(&self.relpath))
+    }
+}
+
+// Set of WADs keyed by their lowercase relative path
+#[derive(Debug, Clone, Default)]
+pub struct WadIndex {
+    pub name: String,
+    pub mounts: BTreeMap<String, MountedWad>,
+}
+
+impl WadIndex {
+    // Index every WAD under Game/DATA/FINAL
+    pub fn from_game_folder(game_path: &Path, no_tft: bool) -> Result<Self, InjectionError> {
+        let final_dir = game_path.join("DATA").join("FINAL");
+        if !final_dir.exists() {
+            return Err(InjectionError::InvalidGamePath(format!(
+                "DATA/FINAL not found in {}", game_path.display()
+            )));
+        }
+
+        let mut index = WadIndex { name: "game".into(), mounts: BTreeMap::new() };
+        let walker = WalkDir::new(&final_dir).into_iter().filter_entry(|e| {
+            !(e.file_type().is_dir() && is_wad_name(&e.file_name().to_string_lossy()))
+        });
+        for entry in walker {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_lowercase();
+            if !entry.file_type().is_file() || !file_name.ends_with(".wad.client") {
+                continue;
+            }
+            let relpath = match wad::generic_relative_path(entry.path(), game_path) {
+                Some(rel) => rel,
+                None => continue,
+            };
+            let archive = WadArchive::read_from_game_file(entry.path())?;
+            index.add_mount(relpath, archive);
+        }
+
+        if no_tft {
+            index.mounts.retain(|_, mounted| !TFT_MOUNTS.contains(&mounted.mount_name().as_str()));
+        }
+        Ok(index)
+    }
+
+    // Index the WAD/ and RAW/ folders of an installed mod
+    pub fn from_mod_folder(mod_dir: &Path) -> Result<Self, InjectionError> {
+        if !mod_dir.join("META").join("info.json").exists() {
+            return Err(InjectionError::OverlayError(format!(
+                "Mod is missing META/info.json: {}", mod_dir.display()
+            )));
+        }
+        let name = mod_dir.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut index = WadIndex { name, mounts: BTreeMap::new() };
+
+        let wad_dir = mod_dir.join("WAD");
+        if wad_dir.exists() {
+            for entry in fs::read_dir(&wad_dir)? {
+                let path = entry?.path();
+                let file_name = path.file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if !is_wad_name(&file_name) {
+                    continue;
+                }
+                let archive = if path.is_dir() {
+                    WadArchive::pack_from_directory(&path)?
+                } else {
+                    WadArchive::read_from_file(&path)?
+                };
+                index.add_mount(format!("WAD/{}", file_name), archive);
+            }
+        }
+
+        // Loose files under RAW/ are packed into a single WAD and matched by content
+        let raw_dir = mod_dir.join("RAW");
+        if raw_dir.exists() {
+            let archive = WadArchive::pack_from_directory(&raw_dir)?;
+            if !archive.entries.is_empty() {
+                index.add_mount("WAD/_RAW.wad.client".into(), archive);
+            }
+        }
+
+        Ok(index)
+    }
+
+    // Index any supported mod input (see normalize.rs) without installing it
+    pub fn from_mod_path(path: &Path) -> Result<Self, InjectionError> {
+        match ModKind::detect(path) {
+            Some(ModKind::Fantome) => Self::from_fantome_file(path),
+            Some(ModKind::Zip) => Self::from_zip_file(path, false),
+            Some(ModKind::Wad) => {
+                let mut index = WadIndex { name: normalize::mod_name(path), mounts: BTreeMap::new() };
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let file_name = if is_wad_name(&file_name) { file_name } else { format!("{}.wad.client", index.name) };
+                index.add_mount(format!("WAD/{}", file_name), WadArchive::read_from_file(path)?);
+                Ok(index)
+            }
+            Some(ModKind::Folder) if path.join("META").join("info.json").exists() && path.join("WAD").is_dir() => {
+                Self::from_mod_folder(path)
+            }
+            Some(ModKind::Folder) => {
+                let mut index = WadIndex { name: normalize::mod_name(path), mounts: BTreeMap::new() };
+                for (file_name, wad_path) in normalize::find_wads(path)? {
+                    let archive = if wad_path.is_dir() {
+                        WadArchive::pack_from_directory(&wad_path)?
+                    } else {
+                        WadArchive::read_from_file(&wad_path)?
+                    };
+                    index.add_mount(format!("WAD/{}", file_name), archive);
+                }
+                if index.is_empty() {
+                    return Err(InjectionError::InvalidFantome(format!(
+                        "No WAD files found in {}", path.display()
+                    )));
+                }
+                Ok(index)
+            }
+            None => Err(InjectionError::InvalidFantome(format!(
+                "Unsupported mod file type: {}", path.display()
+            ))),
+        }
+    }
+
+    // Index a .fantome archive without extracting it to disk
+    pub fn from_fantome_file(fantome_path: &Path) -> Result<Self, InjectionError> {
+        Self::from_zip_file(fantome_path, true)
+    }
+
+    // Fantomes (strict) need META/info.json and keep their WADs under WAD/,
+    // plain zips may have WADs anywhere and no info.json
+    fn from_zip_file(zip_path: &Path, strict: bool) -> Result<Self, InjectionError> {
+        let name = normalize::mod_name(zip_path);
+        let mut index = WadIndex { name, mounts: BTreeMap::new() };
+        let mut archive = ZipArchive::new(fs::File::open(zip_path)?)?;
+
+        let mut has_info = false;
+        let mut packed: BTreeMap<String, WadArchive> = BTreeMap::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_dir() {
+                continue;
+            }
+            let path = file.name().replace('\\', "/");
+            let lower = path.to_lowercase();
+            if lower == "meta/info.json" {
+                has_info = true;
+                continue;
+            }
+            let rest = match lower.strip_prefix("wad/") {
+                Some(_) => &path[4..],
+                None if strict => continue,
+                // Plain zip: start at the first WAD-named component, wherever it is
+                None => {
+                    if lower.starts_with("meta/") || lower.starts_with("raw/") {
+                        continue;
+                    }
+                    let mut offset = None;
+                    let mut pos = 0;
+                    for part in path.split('/') {
+                        if is_wad_name(part) {
+                            offset = Some(pos);
+                            break;
+                        }
+                        pos += part.len() + 1;
+                    }
+                    match offset {
+                        Some(offset) => &path[offset..],
+                        None => continue,
+                    }
+                }
+            };
+            match rest.split_once('/') {
+                // WAD/Ahri.wad.client/assets/... - loose files of a WAD folder
+                Some((wad_name, rel_path)) if is_wad_name(wad_name) => {
+                    let mut bytes = Vec::with_capacity(file.size() as usize);
+                    file.read_to_end(&mut bytes)?;
+                    let target = packed.entry(wad_name.to_string()).or_default();
+                    let hash = wad::hash_path(rel_path);
+                    target.entries.insert(hash, wad::WadEntry::from_raw(bytes));
+                    target.names.insert(hash, rel_path.to_lowercase());
+                }
+                None if is_wad_name(rest) => {
+                    let mut bytes = Vec::with_capacity(file.size() as usize);
+                    file.read_to_end(&mut bytes)?;
+                    index.add_mount(format!("WAD/{}", rest), WadArchive::read_from_bytes(bytes)?);
+                }
+                _ => {}
+            }
+        }
+        for (wad_name, archive) in packed {
+            index.add_mount(format!("WAD/{}", wad_name), archive);
+        }
+
+        if strict && !has_info {
+            return Err(InjectionError::MissingFantomeFile(format!(
+                "{} has no META/info.json", zip_path.display()
+            )));
+        }
+        if index.is_empty() {
+            return Err(InjectionError::MissingFantomeFile(format!(
+                "{} contains no WAD files", zip_path.display()
+            )));
+        }
+        Ok(index)
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.mounts.values().map(|mounted| mounted.archive.entries.len()).sum()
+    }
+
+    fn add_mount(&mut self, relpath: String, archive: WadArchive) {
+        self.mounts.insert(relpath.to_lowercase(), MountedWad { relpath, archive });
+    }
+
+    // SubChunkTOC hashes of every game WAD; mods overriding them crash the game
+    pub fn checksums_to_block(&self) -> HashSet<u64> {
+        self.mounts.values()
+            .map(|mounted| {
+                let path = Path::new(&mounted.relpath).with_extension("SubChunkTOC");
+                wad::hash_path(&path.to_string_lossy().replace('\\', "/"))
+            })
+            .collect()
+    }
+
+    pub fn remove_blocked(&mut self, blocked: &HashSet<u64>) {
+        for mounted in self.mounts.values_mut() {
+            mounted.archive.entries.retain(|name, _| !blocked.contains(name));
+        }
+        self.mounts.retain(|_, mounted| !mounted.archive.entries.is_empty());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mounts.is_empty()
+    }
+
+    // Drop entries that an earlier (lower priority) WAD also provides, later ones win
+    pub fn resolve_conflicts_within(&mut self) {
+        let mut seen = HashSet::new();
+        for mounted in self.mounts.values_mut().rev() {
+            mounted.archive.entries.retain(|name, _| seen.insert(*name));
+        }
+        self.mounts.retain(|_, mounted| !mounted.archive.entries.is_empty());
+    }
+
+    // Remove entries from other that this index overrides
+    pub fn resolve_conflicts_with(&self, other: &mut WadIndex) {
+        let mine: HashSet<u64> = self.mounts.values()
+            .flat_map(|mounted| mounted.archive.entries.keys().cloned())
+            .collect();
+        for mounted in other.mounts.values_mut() {
+            mounted.archive.entries.retain(|name, _| !mine.contains(name));
+        }
+        other.mounts.retain(|_, mounted| !mounted.archive.entries.is_empty());
+    }
+
+    // Merge a mod WAD into the game WAD it targets, plus any other game WAD sharing its entries
+    pub fn add_overlay_mod(&self, overlay: &mut WadIndex, mod_wad: &MountedWad) -> Result<(), InjectionError> {
+        let mount_name = mod_wad.mount_name();
+        let base = self.mounts.iter()
+            .find(|(_, game)| game.mount_name() == mount_name)
+            .or_else(|| {
+                self.mounts.iter()
+                    .map(|(key, game)| (key, game, game.archive.overlap_count(&mod_wad.archive)))
+                    .filter(|(_, _, count)| *count > 0)
+                    .max_by_key(|(_, _, count)| *count)
+                    .map(|(key, game, _)| (key, game))
+            })
+            .map(|(key, _)| key.clone());
+
+        let base = match base {
+            Some(key) => key,
+            None => {
+                return Err(InjectionError::OverlayError(format!(
+                    "Failed to find base wad for: {}", mod_wad.relpath
+                )));
+            }
+        };
+
+        for (key, game) in &self.mounts {
+            let merged = if *key == base {
+                mod_wad.archive.clone()
+            } else {
+                game.archive.overlapping(&mod_wad.archive)
+            };
+            if merged.entries.is_empty() {
+                continue;
+            }
+            let target = overlay.mounts.entry(key.clone()).or_insert_with(|| MountedWad {
+                relpath: game.relpath.clone(),
+                archive: game.archive.clone(),
+            });
+            target.archive.merge_in(&merged);
+        }
+        Ok(())
+    }
+}
+
+// Order mods so the highest priority one comes last and wins conflicts.
+// `priority` lists mod names highest priority first; unlisted mods keep their order and go first.
+pub fn sort_by_priority<T>(items: &mut [T], priority: &[String], name: impl Fn(&T) -> String) {
+    let rank = |item: &T| {
+        let item_name = name(item).to_lowercase();
+        priority.iter()
+            .position(|p| p.to_lowercase() == item_name)
+            .map(|pos| priority.len() - pos)
+            .unwrap_or(0)
+    };
+    items.sort_by_key(|item| rank(item));
+}
+
+pub fn find_conflicts(mod_indexes: &[WadIndex]) -> Vec<ModConflict> {
+    let mut owners: BTreeMap<u64, (Vec<usize>, String)> = BTreeMap::new();
+    for (i, index) in mod_indexes.iter().enumerate() {
+        for mounted in index.mounts.values() {
+            for name in mounted.archive.entries.keys() {
+                let owner = owners.entry(*name).or_insert_with(|| (Vec::new(), String::new()));
+                if !owner.0.contains(&i) {
+                    owner.0.push(i);
+                }
+                if owner.1.is_empty() || mounted.archive.names.contains_key(name) {
+                    owner.1 = mounted.archive.entry_name(*name);
+                }
+            }
+        }
+    }
+
+    let mut grouped: BTreeMap<Vec<usize>, Vec<String>> = BTreeMap::new();
+    for (mods, path) in owners.into_values() {
+        if mods.len() > 1 {
+            grouped.entry(mods).or_default().push(path);
+        }
+    }
+
+    grouped.into_iter()
+        .map(|(mods, mut paths)| {
+            paths.sort();
+            let mods: Vec<String> = mods.iter().map(|i| mod_indexes[*i].name.clone()).collect();
+            ModConflict { winner: mods.last().cloned().unwrap_or_default(), mods, paths }
+        })
+        .collect()
+}
+
+// Load the mods that can take part in an overlay, skipping broken or empty ones
+fn load_mod_indexes(
+    mods_dir: &Path,
+    mod_names: &[String],
+    blocked: &HashSet<u64>,
+    skipped: &mut Vec<String>,
+) -> Vec<WadIndex> {
+    let mut mod_indexes = Vec::new();
+    for name in mod_names {
+        let mut index = match WadIndex::from_mod_folder(&mods_dir.join(name)) {
+            Ok(index) => index,
+            Err(e) => {
+                println!("Skipping mod {}: {}", name, e);
+                skipped.push(name.clone());
+                continue;
+            }
+        };
+        index.remove_blocked(blocked);
+        if index.is_empty() {
+            println!("Skipping mod {}: no WAD entries", name);
+            skipped.push(name.clone());
+            continue;
+        }
+        mod_indexes.push(index);
+    }
+    mod_indexes
+}
+
+// Summary of an overlay build
+#[derive(Debug, Default, Clone)]
+pub struct OverlayReport {
+    pub mods_included: Vec<String>,
+    pub mods_skipped: Vec<String>,
+    pub wads_written: usize,
+    pub wads_unchanged: usize,
+    pub conflicts: Vec<ModConflict>,
+}
+
+// Build the overlay folder consumed by `mod-tools runoverlay`
+// checkpoint is called between mods and WADs so a cancelled or timed out job stops early
+pub fn build_overlay(
+    game_path: &Path,
+    mods_dir: &Path,
+    mod_names: &[String],
+    overlay_dir: &Path,
+    no_tft: bool,
+    checkpoint: &dyn Fn() -> Result<(), InjectionError>,
+) -> Result<OverlayReport, InjectionError> {
+    let mut report = OverlayReport::default();
+
+    println!("Reading game WADs...");
+    let game = WadIndex::from_game_folder(game_path, no_tft)?;
+    let blocked = game.checksums_to_block();
+    checkpoint()?;
+
+    // Later mods take priority over earlier ones
+    let mut mod_indexes = load_mod_indexes(mods_dir, mod_names, &blocked, &mut report.mods_skipped);
+    report.conflicts = find_conflicts(&mod_indexes);
+    for conflict in &report.conflicts {
+        println!(
+            "Conflict between {}: {} entries, {} wins",
+            conflict.mods.join(", "), conflict.paths.len(), conflict.winner
+        );
+    }
+    for i in 0..mod_indexes.len() {
+        let (earlier, rest) = mod_indexes.split_at_mut(i);
+        let index = &mut rest[0];
+        index.resolve_conflicts_within();
+        for lower in earlier.iter_mut() {
+            index.resolve_conflicts_with(lower);
+        }
+    }
+    mod_indexes.retain(|index| {
+        let keep = !index.is_empty();
+        if !keep {
+            report.mods_skipped.push(index.name.clone());
+        }
+        keep
+    });
+
+    println!("Merging {} mods...", mod_indexes.len());
+    let mut overlay = WadIndex { name: "overlay".into(), mounts: BTreeMap::new() };
+    for index in &mod_indexes {
+        checkpoint()?;
+        for mod_wad in index.mounts.values() {
+            game.add_overlay_mod(&mut overlay, mod_wad)?;
+        }
+        report.mods_included.push(index.name.clone());
+    }
+
+    println!("Writing {} WADs...", overlay.mounts.len());
+    fs::create_dir_all(overlay_dir)?;
+    let mut keep: HashSet<PathBuf> = HashSet::new();
+    for mounted in overlay.mounts.values() {
+        checkpoint()?;
+        let path = overlay_dir.join(&mounted.relpath);
+        if mounted.archive.write_to_file(&path)? {
+            report.wads_written += 1;
+        } else {
+            report.wads_unchanged += 1;
+        }
+        keep.insert(path);
+    }
+
+    // Remove WADs left over from a previous overlay
+    for entry in WalkDir::new(overlay_dir) {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_lowercase();
+        if entry.file_type().is_file() && file_name.ends_with(".wad.client") && !keep.contains(path) {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::injection::wad::WadEntry;
+
+    const CHAMPION_WAD: &str = "DATA/FINAL/Champions/Ahri.wad.client";
+
+    fn write_game(game_path: &Path) {
+        let mut archive = WadArchive::new();
+        for (path, bytes) in [("assets/ahri.tex", &b"base texture"[..]), ("assets/ahri.skn", b"base mesh")] {
+            archive.entries.insert(wad::hash_path(path), WadEntry::from_raw(bytes.to_vec()));
+        }
+        archive.write_to_file(&game_path.join(CHAMPION_WAD)).unwrap();
+    }
+
+    fn write_mod(mods_dir: &Path, name: &str, files: &[(&str, &[u8])]) {
+        let mod_dir = mods_dir.join(name);
+        fs::create_dir_all(mod_dir.join("META")).unwrap();
+        fs::write(mod_dir.join("META").join("info.json"), format!(r#"{{"Name":"{}"}}"#, name)).unwrap();
+        for (path, bytes) in files {
+            let path = mod_dir.join("WAD").join("Ahri.wad.client").join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, bytes).unwrap();
+        }
+    }
+
+    fn overlay_file(overlay_dir: &Path, path: &str) -> Vec<u8> {
+        let archive = WadArchive::read_from_file(&overlay_dir.join(CHAMPION_WAD)).unwrap();
+        archive.entries[&wad::hash_path(path)].decompressed().unwrap()
+    }
+
+    #[test]
+    fn later_mod_wins_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (game_path, mods_dir) = (dir.path().join("Game"), dir.path().join("mods"));
+        write_game(&game_path);
+        write_mod(&mods_dir, "Arcade Ahri", &[("assets/ahri.tex", b"arcade texture"), ("assets/ahri.skn", b"arcade mesh")]);
+        write_mod(&mods_dir, "Star Guardian Ahri", &[("assets/ahri.tex", b"star guardian texture")]);
+
+        // Highest priority first, so it is sorted last and built last
+        let mut mod_names = vec!["Star Guardian Ahri".to_string(), "Arcade Ahri".to_string()];
+        sort_by_priority(&mut mod_names, &["Star Guardian Ahri".to_string()], |name| name.clone());
+        assert_eq!(mod_names, ["Arcade Ahri", "Star Guardian Ahri"]);
+
+        let overlay_dir = dir.path().join("overlay");
+        let report = build_overlay(&game_path, &mods_dir, &mod_names, &overlay_dir, false, &|| Ok(())).unwrap();
+        assert_eq!(report.mods_included, mod_names);
+        assert!(report.mods_skipped.is_empty());
+        assert_eq!(report.wads_written, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].mods, mod_names);
+        assert_eq!(report.conflicts[0].winner, "Star Guardian Ahri");
+        assert_eq!(report.conflicts[0].paths, ["assets/ahri.tex"]);
+
+        assert_eq!(overlay_file(&overlay_dir, "assets/ahri.tex"), b"star guardian texture");
+        assert_eq!(overlay_file(&overlay_dir, "assets/ahri.skn"), b"arcade mesh");
+
+        // Same loadout again: nothing to rewrite
+        let report = build_overlay(&game_path, &mods_dir, &mod_names, &overlay_dir, false, &|| Ok(())).unwrap();
+        assert_eq!((report.wads_written, report.wads_unchanged), (0, 1));
+    }
+
+    #[test]
+    fn fully_overridden_mod_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let (game_path, mods_dir) = (dir.path().join("Game"), dir.path().join("mods"));
+        write_game(&game_path);
+        write_mod(&mods_dir, "Old", &[("assets/ahri.tex", b"old texture")]);
+        write_mod(&mods_dir, "New", &[("assets/ahri.tex", b"new texture")]);
+
+        let mod_names = vec!["Old".to_string(), "New".to_string()];
+        let overlay_dir = dir.path().join("overlay");
+        let report = build_overlay(&game_path, &mods_dir, &mod_names, &overlay_dir, false, &|| Ok(())).unwrap();
+        assert_eq!(report.mods_included, ["New"]);
+        assert_eq!(report.mods_skipped, ["Old"]);
+        assert_eq!(report.conflicts[0].winner, "New");
+        assert_eq!(overlay_file(&overlay_dir, "assets/ahri.tex"), b"new texture");
+        assert_eq!(overlay_file(&overlay_dir, "assets/ahri.skn"), b"base mesh");
+    }
+}
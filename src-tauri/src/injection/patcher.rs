@@ -0,0 +1,382 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use wait_timeout::ChildExt;
+
+use crate::injection::types::{InjectionProgress, ModState, PatcherMessage};
+
+// Supervisor for the `mod-tools runoverlay` child process
+// The injector hands over the spawned child; a background thread then owns it,
+// forwards its output, reports lifecycle changes and restarts it when it crashes
+// during a game.
+
+// How many times a crashed patcher is restarted before giving up
+const MAX_RESTARTS: u32 = 3;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const GRACEFUL_STOP_TIMEOUT: Duration = Duration::from_secs(3);
+// A patcher that ran this long before crashing gets a fresh set of restarts
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+// The patcher that is currently running, if any
+static ACTIVE_PATCHER: Lazy<Mutex<Option<PatcherSupervisor>>> = Lazy::new(|| Mutex::new(None));
+
+// Whether the game is running, kept up to date by the LCU watcher. Outside of a game
+// there is nothing to patch, so a patcher that dies then is left down.
+static GAME_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatcherLifecycle {
+    Started,
+    Exited,
+    Restarting,
+    Stopped,
+    Failed,
+}
+
+// Payload of the "patcher-lifecycle" event
+#[derive(Debug, Clone, Serialize)]
+pub struct PatcherLifecycleEvent {
+    pub lifecycle: PatcherLifecycle,
+    pub state: ModState,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub restarts: u32,
+    pub last_output: Option<String>,
+}
+
+// Builds a fresh runoverlay command for every (re)start
+pub type CommandFactory = Box<dyn Fn() -> Command + Send + 'static>;
+
+pub struct PatcherSupervisor {
+    stop_requested: Arc<AtomicBool>,
+    state: Arc<Mutex<ModState>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl PatcherSupervisor {
+    // Spawn the first child synchronously so start errors reach the caller
+    pub fn start(make_command: CommandFactory, app_handle: Option<AppHandle>) -> io::Result<Self> {
+        let child = spawn_child(&make_command, &app_handle)?;
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(Mutex::new(ModState::Running));
+
+        let thread = {
+            let stop_requested = stop_requested.clone();
+            let state = state.clone();
+            thread::spawn(move || supervise(child, make_command, app_handle, stop_requested, state))
+        };
+
+        Ok(Self { stop_requested, state, thread: Some(thread) })
+    }
+
+    pub fn state(&self) -> ModState {
+        *self.state.lock().unwrap()
+    }
+
+    // Ask the patcher to exit and wait for the supervisor thread
+    pub fn stop(mut self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// Register a freshly started patcher as the active one, stopping any previous
+pub fn set_active_patcher(supervisor: PatcherSupervisor) {
+    let previous = ACTIVE_PATCHER.lock().unwrap().replace(supervisor);
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+}
+
+// Stop the active patcher, if there is one. Returns true when something was stopped.
+pub fn stop_active_patcher() -> bool {
+    let active = ACTIVE_PATCHER.lock().unwrap().take();
+    match active {
+        Some(supervisor) => {
+            supervisor.stop();
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn active_patcher_state() -> Option<ModState> {
+    ACTIVE_PATCHER.lock().unwrap().as_ref().map(|s| s.state())
+}
+
+pub fn set_game_active(active: bool) {
+    GAME_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+fn game_active() -> bool {
+    GAME_ACTIVE.load(Ordering::SeqCst)
+}
+
+struct RunningChild {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    last_output: Arc<Mutex<Option<String>>>,
+    readers: Vec<JoinHandle<()>>,
+}
+
+fn spawn_child(make_command: &CommandFactory, app_handle: &Option<AppHandle>) -> io::Result<RunningChild> {
+    let mut command = make_command();
+    command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+
+    let last_output = Arc::new(Mutex::new(None));
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward_output(stdout, last_output.clone(), app_handle.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward_output(stderr, last_output.clone(), None));
+    }
+    let stdin = child.stdin.take();
+    Ok(RunningChild { child, stdin, last_output, readers })
+}
+
+// Log the child's output and turn its status lines into "injection-progress" events
+fn forward_output<R: Read + Send + 'static>(
+    stream: R,
+    last_output: Arc<Mutex<Option<String>>>,
+    app_handle: Option<AppHandle>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut current: Option<PatcherMessage> = None;
+        for line in BufReader::new(stream).lines() {
+            let line = match line {
+                Ok(line) => line.trim().to_string(),
+                Err(_) => break,
+            };
+            if line.is_empty() {
+                continue;
+            }
+            println!("[mod-tools] {}", line);
+
+            let progress = if let Some(message) = PatcherMessage::from_status_line(&line) {
+                current = Some(message);
+                Some((message, None))
+            } else if let Some(detail) = line.strip_prefix("Config:").or_else(|| line.strip_prefix("[DLL]")) {
+                current.map(|message| (message, Some(detail.trim().to_string())))
+            } else {
+                None
+            };
+            if let (Some((message, detail)), Some(app)) = (progress, &app_handle) {
+                let _ = app.emit("injection-progress", InjectionProgress {
+                    message,
+                    text: message.to_string(),
+                    detail,
+                });
+            }
+
+            *last_output.lock().unwrap() = Some(line);
+        }
+    })
+}
+
+fn emit(app_handle: &Option<AppHandle>, event: PatcherLifecycleEvent) {
+    println!("Patcher {:?} (pid={:?}, exit_code={:?}, restarts={})",
+        event.lifecycle, event.pid, event.exit_code, event.restarts);
+    if let Some(app) = app_handle {
+        let _ = app.emit("patcher-lifecycle", event);
+    }
+}
+
+// runoverlay exits on a newline from stdin; kill it if it doesn't in time
+fn stop_child(running: &mut RunningChild) -> Option<i32> {
+    if let Some(stdin) = running.stdin.as_mut() {
+        let _ = stdin.write_all(b"\n");
+        let _ = stdin.flush();
+    }
+    match running.child.wait_timeout(GRACEFUL_STOP_TIMEOUT) {
+        Ok(Some(status)) => status.code(),
+        _ => {
+            let _ = running.child.kill();
+            running.child.wait().ok().and_then(|status| status.code())
+        }
+    }
+}
+
+fn supervise(
+    mut running: RunningChild,
+    make_command: CommandFactory,
+    app_handle: Option<AppHandle>,
+    stop_requested: Arc<AtomicBool>,
+    state: Arc<Mutex<ModState>>,
+) {
+    let mut restarts = 0;
+    let set_state = |new_state: ModState| *state.lock().unwrap() = new_state;
+
+    loop {
+        let pid = running.child.id();
+        let started_at = Instant::now();
+        emit(&app_handle, PatcherLifecycleEvent {
+            lifecycle: PatcherLifecycle::Started,
+            state: ModState::Running,
+            pid: Some(pid),
+            exit_code: None,
+            restarts,
+            last_output: None,
+        });
+
+        // Wait for the child to exit or for a stop request
+        let status: Option<ExitStatus> = loop {
+            if stop_requested.load(Ordering::SeqCst) {
+                let code = stop_child(&mut running);
+                for reader in running.readers.drain(..) {
+                    let _ = reader.join();
+                }
+                set_state(ModState::Idle);
+                emit(&app_handle, PatcherLifecycleEvent {
+                    lifecycle: PatcherLifecycle::Stopped,
+                    state: ModState::Idle,
+                    pid: Some(pid),
+                    exit_code: code,
+                    restarts,
+                    last_output: running.last_output.lock().unwrap().clone(),
+                });
+                return;
+            }
+            match running.child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => {
+                    println!("Failed to query patcher status: {}", e);
+                    break None;
+                }
+            }
+        };
+
+        for reader in running.readers.drain(..) {
+            let _ = reader.join();
+        }
+        let last_output = running.last_output.lock().unwrap().clone();
+        let exit_code = status.and_then(|status| status.code());
+        // A non-zero code, a signal, or a status we couldn't read
+        let crashed = !matches!(status, Some(status) if status.success());
+        let restart = crashed && game_active();
+        emit(&app_handle, PatcherLifecycleEvent {
+            lifecycle: PatcherLifecycle::Exited,
+            state: if restart { ModState::Busy } else { ModState::Idle },
+            pid: Some(pid),
+            exit_code,
+            restarts,
+            last_output: last_output.clone(),
+        });
+
+        // A clean exit means the patcher is done, and outside of a game there is nothing to patch
+        if !restart {
+            if crashed {
+                println!("Patcher exited outside of a game, not restarting it");
+            }
+            set_state(ModState::Idle);
+            return;
+        }
+        if started_at.elapsed() >= STABLE_RUN {
+            restarts = 0;
+        }
+
+        // Nobody asked it to stop, so the overlay is gone while the game needs it
+        if restarts >= MAX_RESTARTS {
+            set_state(ModState::CriticalError);
+            emit(&app_handle, PatcherLifecycleEvent {
+                lifecycle: PatcherLifecycle::Failed,
+                state: ModState::CriticalError,
+                pid: None,
+                exit_code,
+                restarts,
+                last_output: last_output.clone(),
+            });
+            if let Some(app) = &app_handle {
+                let _ = app.emit("injection-status", "error");
+                let _ = app.emit("skin-injection-error", format!(
+                    "Overlay process keeps exiting (code {:?}): {}",
+                    exit_code, last_output.unwrap_or_default()
+                ));
+            }
+            return;
+        }
+
+        restarts += 1;
+        set_state(ModState::Busy);
+        emit(&app_handle, PatcherLifecycleEvent {
+            lifecycle: PatcherLifecycle::Restarting,
+            state: ModState::Busy,
+            pid: None,
+            exit_code,
+            restarts,
+            last_output,
+        });
+        thread::sleep(Duration::from_secs(restarts as u64));
+        if stop_requested.load(Ordering::SeqCst) {
+            set_state(ModState::Idle);
+            return;
+        }
+
+        running = match spawn_child(&make_command, &app_handle) {
+            Ok(child) => child,
+            Err(e) => {
+                println!("Failed to restart patcher: {}", e);
+                set_state(ModState::CriticalError);
+                emit(&app_handle, PatcherLifecycleEvent {
+                    lifecycle: PatcherLifecycle::Failed,
+                    state: ModState::CriticalError,
+                    pid: None,
+                    exit_code: None,
+                    restarts,
+                    last_output: Some(e.to_string()),
+                });
+                return;
+            }
+        };
+        set_state(ModState::Running);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    // Supervise `sh -c <script>` and wait for the supervisor to give up on it
+    fn run(script: &'static str) -> (ModState, usize) {
+        let spawns = Arc::new(AtomicUsize::new(0));
+        let counter = spawns.clone();
+        let supervisor = PatcherSupervisor::start(Box::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(script);
+            command
+        }), None).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while matches!(supervisor.state(), ModState::Running | ModState::Busy) && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
+        }
+        let state = supervisor.state();
+        supervisor.stop();
+        (state, spawns.load(Ordering::SeqCst))
+    }
+
+    // One test, since the game flag is shared by every supervisor
+    #[test]
+    fn restarts_only_crashes_during_a_game() {
+        set_game_active(false);
+        assert_eq!(run("exit 3"), (ModState::Idle, 1));
+
+        set_game_active(true);
+        assert_eq!(run("exit 0"), (ModState::Idle, 1));
+        assert_eq!(run("kill -9 $$"), (ModState::CriticalError, 1 + MAX_RESTARTS as usize));
+        set_game_active(false);
+    }
+}
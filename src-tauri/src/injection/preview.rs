@@ -0,0 +1,122 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+use crate::injection::error::InjectionError;
+use crate::injection::normalize::ModKind;
+
+// Preview images for custom skins
+// cslol-manager stores a mod's picture as META/image.png. It is copied out of the
+// archive or folder into the thumbnail cache; mods without one get a generated SVG
+// card (initials on a colour picked from the content hash) so every skin has a preview.
+
+const MAX_IMAGE_SIZE: u64 = 16 * 1024 * 1024;
+const IMAGE_NAMES: [&str; 3] = ["meta/image.png", "meta/image.jpg", "meta/image.jpeg"];
+
+// File extension for image data, None when it isn't a PNG or JPEG
+fn image_extension(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else {
+        None
+    }
+}
+
+// META/image.* of a fantome, zip or mod folder
+fn read_mod_image(path: &Path) -> Result<Option<Vec<u8>>, InjectionError> {
+    let mut data = Vec::new();
+    match ModKind::detect(path) {
+        Some(ModKind::Fantome) | Some(ModKind::Zip) => {
+            let mut archive = ZipArchive::new(File::open(path)?)?;
+            let name = archive.file_names()
+                .find(|name| IMAGE_NAMES.contains(&name.replace('\\', "/").to_lowercase().as_str()))
+                .map(String::from);
+            let file = match name {
+                Some(name) => archive.by_name(&name)?,
+                None => return Ok(None),
+            };
+            if file.size() > MAX_IMAGE_SIZE {
+                return Ok(None);
+            }
+            file.take(MAX_IMAGE_SIZE).read_to_end(&mut data)?;
+        }
+        Some(ModKind::Folder) => {
+            let image = fs::read_dir(path.join("META")).ok()
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .find(|e| IMAGE_NAMES.contains(&format!("meta/{}", e.file_name().to_string_lossy().to_lowercase()).as_str()));
+            let image = match image {
+                Some(image) if image.metadata()?.len() <= MAX_IMAGE_SIZE => image.path(),
+                _ => return Ok(None),
+            };
+            data = fs::read(image)?;
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(data))
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// 16:9 card with the skin's initials and name
+fn placeholder_svg(title: &str, subtitle: &str, seed: &str) -> String {
+    let hue = seed.bytes().fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32)) % 360;
+    let initials: String = title.split_whitespace()
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect();
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="480" height="270" viewBox="0 0 480 270">
+<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">
+<stop offset="0" stop-color="hsl({hue},55%,38%)"/><stop offset="1" stop-color="hsl({hue2},55%,18%)"/>
+</linearGradient></defs>
+<rect width="480" height="270" fill="url(#bg)"/>
+<text x="240" y="150" font-family="sans-serif" font-size="96" font-weight="700" fill="#fff" fill-opacity="0.9" text-anchor="middle">{initials}</text>
+<text x="240" y="210" font-family="sans-serif" font-size="22" fill="#fff" fill-opacity="0.8" text-anchor="middle">{title}</text>
+<text x="240" y="240" font-family="sans-serif" font-size="16" fill="#fff" fill-opacity="0.6" text-anchor="middle">{subtitle}</text>
+</svg>
+"##,
+        hue = hue,
+        hue2 = (hue + 40) % 360,
+        initials = escape_xml(&initials),
+        title = escape_xml(title),
+        subtitle = escape_xml(subtitle),
+    )
+}
+
+// Write the preview of the mod at path to <out_base>.png/.jpg, or a placeholder to
+// <out_base>.svg, and return where it went.
+pub fn write_preview(
+    path: &Path,
+    out_base: &Path,
+    title: &str,
+    subtitle: &str,
+    seed: &str,
+) -> Result<PathBuf, InjectionError> {
+    if let Some(parent) = out_base.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // An unreadable image isn't worth failing an import over
+    let image = read_mod_image(path).unwrap_or_else(|e| {
+        println!("Failed to read preview image of {}: {}", path.display(), e);
+        None
+    });
+    if let Some(data) = image {
+        if let Some(ext) = image_extension(&data) {
+            let target = out_base.with_extension(ext);
+            fs::write(&target, data)?;
+            return Ok(target);
+        }
+        println!("Preview image of {} is not a PNG or JPEG, using a placeholder", path.display());
+    }
+    let target = out_base.with_extension("svg");
+    fs::write(&target, placeholder_svg(title, subtitle, seed))?;
+    Ok(target)
+}
@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::injection::error::InjectionError;
+
+// Staged replacement of Game/mods
+// New mods are prepared in Game/mods.staging and only swapped in once all of them
+// processed fine. The previous folder is kept as Game/mods.backup until commit(),
+// and put back if the transaction is rolled back or dropped without committing.
+pub struct ModsTransaction {
+    mods_dir: PathBuf,
+    staging_dir: PathBuf,
+    backup_dir: PathBuf,
+    swapped: bool,
+    finished: bool,
+}
+
+impl ModsTransaction {
+    pub fn begin(game_path: &Path) -> Result<Self, InjectionError> {
+        let mods_dir = game_path.join("mods");
+        let staging_dir = game_path.join("mods.staging");
+        let backup_dir = game_path.join("mods.backup");
+
+        // Recover from a transaction interrupted mid-swap (crash, power loss)
+        if backup_dir.exists() {
+            if mods_dir.exists() {
+                fs::remove_dir_all(&backup_dir)?;
+            } else {
+                println!("Restoring Game/mods from an interrupted injection");
+                fs::rename(&backup_dir, &mods_dir)?;
+            }
+        }
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        fs::create_dir_all(&staging_dir)?;
+
+        Ok(Self { mods_dir, staging_dir, backup_dir, swapped: false, finished: false })
+    }
+
+    // Folder to install the new mods into
+    pub fn staging_dir(&self) -> &Path {
+        &self.staging_dir
+    }
+
+    // Make the staged mods the live Game/mods, keeping the old folder as backup
+    pub fn swap(&mut self) -> Result<(), InjectionError> {
+        if self.mods_dir.exists() {
+            fs::rename(&self.mods_dir, &self.backup_dir)?;
+        }
+        if let Err(e) = fs::rename(&self.staging_dir, &self.mods_dir) {
+            if self.backup_dir.exists() {
+                let _ = fs::rename(&self.backup_dir, &self.mods_dir);
+            }
+            return Err(InjectionError::IoError(e));
+        }
+        self.swapped = true;
+        Ok(())
+    }
+
+    // Keep the new mods and drop the backup
+    pub fn commit(mut self) -> Result<(), InjectionError> {
+        self.finished = true;
+        if self.backup_dir.exists() {
+            fs::remove_dir_all(&self.backup_dir)?;
+        }
+        Ok(())
+    }
+
+    // Put the previous Game/mods back
+    pub fn rollback(mut self) -> Result<(), InjectionError> {
+        self.restore()
+    }
+
+    fn restore(&mut self) -> Result<(), InjectionError> {
+        self.finished = true;
+        if self.staging_dir.exists() {
+            fs::remove_dir_all(&self.staging_dir)?;
+        }
+        if self.swapped {
+            if self.mods_dir.exists() {
+                fs::remove_dir_all(&self.mods_dir)?;
+            }
+            if self.backup_dir.exists() {
+                fs::rename(&self.backup_dir, &self.mods_dir)?;
+            }
+            self.swapped = false;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ModsTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Err(e) = self.restore() {
+                println!("Failed to roll back Game/mods: {}", e);
+            }
+        }
+    }
+}
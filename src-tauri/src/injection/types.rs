@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use std::time::Instant;
 
 // Define the types we need
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Skin {
     pub champion_id: u32,
     pub skin_id: u32,
@@ -12,20 +12,53 @@ pub struct Skin {
     pub fantome_path: Option<String>, // Add fantome path from the JSON
 }
 
+// Entries provided by more than one mod, grouped by the set of mods involved
+#[derive(Debug, Clone, Serialize)]
+pub struct ModConflict {
+    pub mods: Vec<String>, // In build order, the last one wins
+    pub winner: String,
+    pub paths: Vec<String>,
+}
+
+// A mod that would be included by an injection
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedMod {
+    pub champion_id: u32,
+    pub skin_id: u32,
+    pub chroma_id: Option<u32>,
+    pub mod_name: String,
+    pub fantome_path: String,
+    pub file_size: u64,
+    pub wad_count: usize,
+    pub entry_count: usize,
+    pub error: Option<String>, // Set when the archive is not a usable mod
+}
+
+// Result of a dry run: what inject_skins would do, without touching the game folder
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectionPlan {
+    pub mods: Vec<PlannedMod>,
+    pub missing: Vec<Skin>,
+    pub conflicts: Vec<ModConflict>,
+    pub total_size: u64,
+    pub cached: bool, // An overlay for this exact loadout is already built
+    pub can_inject: bool,
+}
+
 // ModState enum - Similar to CS LOL Manager's state machine
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ModState {
     Uninitialized,
     Idle,
     Busy,
     Running,
-    #[allow(dead_code)]
     CriticalError,
 }
 
 // This represents a message event for the patcher
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PatcherMessage {
     WaitStart,
     Found,
@@ -39,7 +72,24 @@ pub enum PatcherMessage {
 }
 
 impl PatcherMessage {
-    #[allow(dead_code)]
+    const ALL: [PatcherMessage; 9] = [
+        Self::WaitStart,
+        Self::Found,
+        Self::WaitInit,
+        Self::Scan,
+        Self::NeedSave,
+        Self::WaitPatchable,
+        Self::Patch,
+        Self::WaitExit,
+        Self::Done,
+    ];
+
+    // Parse a "Status: <message>" line printed by `mod-tools runoverlay`
+    pub fn from_status_line(line: &str) -> Option<Self> {
+        let status = line.trim().strip_prefix("Status:")?.trim();
+        Self::ALL.iter().copied().find(|msg| msg.to_string() == status)
+    }
+
     pub fn to_string(&self) -> &'static str {
         match self {
             Self::WaitStart => "Waiting for league match to start",
@@ -55,20 +105,89 @@ impl PatcherMessage {
     }
 }
 
+// Per-step limits for an injection job, in seconds (stored in config.json)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InjectionTimeouts {
+    pub extraction_secs: u64,    // Resolving, hashing and extracting the fantome files
+    pub overlay_build_secs: u64, // Building the overlay WADs (mkoverlay)
+    pub patcher_start_secs: u64, // Spawning runoverlay, including retries
+}
+
+impl Default for InjectionTimeouts {
+    fn default() -> Self {
+        Self {
+            extraction_secs: 120,
+            overlay_build_secs: 300,
+            patcher_start_secs: 30,
+        }
+    }
+}
+
+// Payload of the "injection-progress" event, one per patcher status change
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectionProgress {
+    pub message: PatcherMessage,
+    pub text: &'static str,
+    pub detail: Option<String>, // Extra "Config:" / "[DLL]" output for the current status
+}
+
+// Catalog key: (champion_id, skin_id, chroma_id)
+pub type SkinKey = (u32, u32, Option<u32>);
+
+// Size and mtime of an indexed file, to tell whether it changed since it was read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: u64,
+}
+
+// A mod file below the champions folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub path: String, // Relative to the index root, with forward slashes
+    pub stamp: FileStamp,
+}
+
+// One skin or chroma listed in a champion JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub skin_id: u32,
+    pub chroma_id: Option<u32>,
+    pub fantome: String, // Path from the JSON, e.g. "aatrox/lunar_eclipse_aatrox.fantome"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedChampion {
+    pub id: u32,
+    pub name: String,  // Display name, e.g. "Wukong"
+    pub alias: String, // Internal name used in game paths, e.g. "MonkeyKing"
+    pub json: FileStamp,
+    pub entries: Vec<CatalogEntry>,
+}
+
 // FileIndex struct to cache paths and champion data
-#[derive(Debug, Default)]
+// Saved to file_index.json next to the champions folder and kept current by a watcher
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct FileIndex {
-    // Map champion_id to champion name
+    pub version: u32,
+    // Directory the index was built from (app_data_dir/champions)
+    pub root: Option<PathBuf>,
+    // Parsed champion JSON by champion folder name
+    pub champions: HashMap<String, IndexedChampion>,
+    // Mod files by lowercase relative path
+    pub mod_files: HashMap<String, IndexedFile>,
+    // Map champion_id to champion folder name
+    #[serde(skip)]
     pub champion_names: HashMap<u32, String>,
-    // Map (champion_id, skin_id) to fantome file path
-    pub skin_paths: HashMap<(u32, Option<u32>), Vec<PathBuf>>,
-    // Map champion name to champion ID
-    pub champion_ids: HashMap<String, u32>,
-    // Track all discovered fantome files
-    pub all_fantome_files: Vec<(PathBuf, Instant)>,
-    // Track fantome files by filename for quick lookup
-    pub fantome_by_filename: HashMap<String, PathBuf>,
-    // Last time the index was built
+    // Map (champion_id, skin_id, chroma_id) to the fantome path listed in the champion JSON
+    #[serde(skip)]
+    pub skin_paths: HashMap<SkinKey, String>,
+    // Map lowercase file name to a key of mod_files
+    #[serde(skip)]
+    pub fantome_by_filename: HashMap<String, String>,
+    // Last time the index was synced with the disk
+    #[serde(skip)]
     pub last_indexed: Option<Instant>,
 }
 
@@ -1,191 +1,593 @@
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Instant, Duration};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache};
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use tauri::{AppHandle, Manager};
 use walkdir::WalkDir;
 
+use crate::injection::cache::modified_secs;
 use crate::injection::error::InjectionError;
-use crate::injection::types::FileIndex;
+use crate::injection::normalize::ModKind;
+use crate::injection::types::{CatalogEntry, FileIndex, FileStamp, IndexedChampion, IndexedFile, Skin};
 
 // Create a global static instance for caching across the application
 pub static GLOBAL_FILE_INDEX: Lazy<Arc<Mutex<FileIndex>>> = Lazy::new(|| {
     Arc::new(Mutex::new(FileIndex::new()))
 });
 
+// Watcher on the champions folder that keeps GLOBAL_FILE_INDEX current
+type IndexWatcher = (PathBuf, Debouncer<RecommendedWatcher, RecommendedCache>);
+static INDEX_WATCHER: Lazy<Mutex<Option<IndexWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+// Bump when FileIndex's saved layout changes to force a rescan
+const INDEX_FORMAT_VERSION: u32 = 2;
+const INDEX_FILE: &str = "file_index.json";
+
+// Wait for a burst of file events (a download, an unzip) to settle before applying it
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+// Without a watcher, sync with the disk after a miss at most this often
+const MISS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+// Fields of champions/<name>/<name>.json the index needs
+#[derive(Deserialize)]
+struct ChampionData {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    alias: String,
+    #[serde(default)]
+    skins: Vec<SkinData>,
+}
+
+#[derive(Deserialize)]
+struct SkinData {
+    id: u32,
+    #[serde(default)]
+    fantome: Option<String>,
+    #[serde(default)]
+    chromas: Vec<ChromaData>,
+}
+
+#[derive(Deserialize)]
+struct ChromaData {
+    id: u32,
+    #[serde(default)]
+    fantome: Option<String>,
+}
+
+fn stamp(metadata: &fs::Metadata) -> FileStamp {
+    FileStamp {
+        size: metadata.len(),
+        modified: modified_secs(metadata),
+    }
+}
+
+// "Aatrox\\Skin.fantome" -> "aatrox/skin.fantome"
+fn normalize_key(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").trim_start_matches('/').to_lowercase()
+}
+
+fn key_file_name(key: &str) -> &str {
+    key.rsplit('/').next().unwrap_or(key)
+}
+
+// Relative path of path below root, with forward slashes
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    Some(rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+// Folder name when rel is a champion JSON ("Aatrox/Aatrox.json")
+fn champion_json_dir(rel: &str) -> Option<&str> {
+    let (dir, file) = rel.split_once('/')?;
+    if file.eq_ignore_ascii_case(&format!("{}.json", dir)) { Some(dir) } else { None }
+}
+
+// What is on disk below a folder of the index, stat only. Taken without the index
+// lock and applied with FileIndex::apply_scan.
+struct DiskScan {
+    prefix: String,
+    champions: Vec<(String, PathBuf)>, // Champion folder and its JSON
+    mod_files: Vec<(String, FileStamp)>, // Relative path and stamp
+}
+
+fn scan_dir(root: &Path, dir: &Path) -> Result<Option<DiskScan>, InjectionError> {
+    let prefix = match relative_path(root, dir) {
+        Some(rel) => normalize_key(&rel),
+        None => return Ok(None),
+    };
+    let mut scan = DiskScan { prefix, champions: Vec::new(), mod_files: Vec::new() };
+    for entry in WalkDir::new(dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = match relative_path(root, entry.path()) {
+            Some(rel) => rel,
+            None => continue,
+        };
+        if let Some(champion_dir) = champion_json_dir(&rel) {
+            scan.champions.push((champion_dir.to_string(), entry.path().to_path_buf()));
+        } else if ModKind::detect(entry.path()).is_some() {
+            scan.mod_files.push((rel, stamp(&entry.metadata()?)));
+        }
+    }
+    Ok(Some(scan))
+}
+
 impl FileIndex {
-    // Index all champions in a directory
-    pub fn index_champions(&mut self, champions_dir: &Path) -> Result<(), InjectionError> {
-        println!("Indexing champions in {}", champions_dir.display());
+    // Saved index for champions_dir, or a full scan when there is none (first start)
+    pub fn open(champions_dir: &Path) -> Result<Self, InjectionError> {
+        let store = Self::store_path(champions_dir);
+        let stored = fs::read_to_string(&store)
+            .ok()
+            .and_then(|content| serde_json::from_str::<FileIndex>(&content).ok())
+            .filter(|index| index.version == INDEX_FORMAT_VERSION && index.root.as_deref() == Some(champions_dir));
+        if let Some(mut index) = stored {
+            index.rebuild_lookups();
+            println!("Loaded file index with {} champions and {} mod files",
+                index.champions.len(), index.mod_files.len());
+            return Ok(index);
+        }
+
+        let mut index = Self::new();
+        index.build(champions_dir)?;
+        index.save()?;
+        Ok(index)
+    }
+
+    fn store_path(champions_dir: &Path) -> PathBuf {
+        champions_dir.with_file_name(INDEX_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), InjectionError> {
+        let root = match &self.root {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+        let content = serde_json::to_string(self)
+            .map_err(|e| InjectionError::ConfigError(format!("Failed to serialize file index: {}", e)))?;
+        fs::write(Self::store_path(root), content)?;
+        Ok(())
+    }
+
+    // Rebuild everything from a champions directory
+    pub fn build(&mut self, champions_dir: &Path) -> Result<(), InjectionError> {
+        println!("Indexing champions and mod files in {}", champions_dir.display());
         let start = Instant::now();
-        
-        if !champions_dir.exists() {
-            return Err(InjectionError::IoError(io::Error::new(
-                io::ErrorKind::NotFound, 
-                format!("Champions directory not found: {}", champions_dir.display())
-            )));
-        }
-        
-        let entries = fs::read_dir(champions_dir)?;
-        
-        for entry in entries.filter_map(Result::ok) {
-            let path = entry.path();
-            if path.is_dir() {
-                let dir_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                
-                // Look for champion ID file
-                let champion_id_file = path.join("champion_id.txt");
-                if champion_id_file.exists() {
-                    if let Ok(id_str) = fs::read_to_string(&champion_id_file) {
-                        if let Ok(champ_id) = id_str.trim().parse::<u32>() {
-                            self.champion_names.insert(champ_id, dir_name.clone());
-                            self.champion_ids.insert(dir_name, champ_id);
-                        }
-                    }
+        *self = Self::new();
+        self.version = INDEX_FORMAT_VERSION;
+        self.root = Some(champions_dir.to_path_buf());
+
+        // Nothing downloaded yet, custom skins are still found by their path
+        if champions_dir.exists() {
+            self.sync_under(champions_dir)?;
+        }
+        self.rebuild_lookups();
+        println!("Indexed {} champions ({} skins and chromas) and {} mod files in {:?}",
+            self.champions.len(), self.skin_paths.len(), self.mod_files.len(), start.elapsed());
+        Ok(())
+    }
+
+    // Bring everything below dir (the root or a folder in it) in line with the disk.
+    // Only champion JSON whose size or mtime changed is parsed again.
+    fn sync_under(&mut self, dir: &Path) -> Result<bool, InjectionError> {
+        let scan = match &self.root {
+            Some(root) => scan_dir(root, dir)?,
+            None => None,
+        };
+        match scan {
+            Some(scan) => self.apply_scan(scan),
+            None => Ok(false),
+        }
+    }
+
+    fn apply_scan(&mut self, scan: DiskScan) -> Result<bool, InjectionError> {
+        let mut changed = false;
+        let mut seen_files = HashSet::new();
+        let mut seen_champions = HashSet::new();
+
+        for (champion_dir, json_file) in &scan.champions {
+            seen_champions.insert(champion_dir.clone());
+            changed |= self.update_champion(champion_dir, json_file)?;
+        }
+        for (rel, file_stamp) in scan.mod_files {
+            let key = normalize_key(&rel);
+            seen_files.insert(key.clone());
+            changed |= self.update_mod_file(key, rel, file_stamp);
+        }
+
+        // Forget whatever disappeared below the scanned folder
+        let prefix = scan.prefix;
+        let under = |key: &str| prefix.is_empty() || key == prefix || key.starts_with(&format!("{}/", prefix));
+        let files_before = self.mod_files.len();
+        self.mod_files.retain(|key, _| !under(key) || seen_files.contains(key));
+        let champions_before = self.champions.len();
+        self.champions.retain(|dir, _| !under(&dir.to_lowercase()) || seen_champions.contains(dir));
+        changed |= files_before != self.mod_files.len() || champions_before != self.champions.len();
+
+        self.last_indexed = Some(Instant::now());
+        Ok(changed)
+    }
+
+    fn update_mod_file(&mut self, key: String, path: String, new_stamp: FileStamp) -> bool {
+        match self.mod_files.get(&key) {
+            Some(file) if file.stamp == new_stamp && file.path == path => false,
+            _ => {
+                self.mod_files.insert(key, IndexedFile { path, stamp: new_stamp });
+                true
+            }
+        }
+    }
+
+    // Parse a champion JSON again if it changed since it was indexed
+    fn update_champion(&mut self, champion_dir: &str, json_file: &Path) -> Result<bool, InjectionError> {
+        let json_stamp = stamp(&fs::metadata(json_file)?);
+        if self.champions.get(champion_dir).map(|c| c.json == json_stamp).unwrap_or(false) {
+            return Ok(false);
+        }
+
+        let champion = match fs::read_to_string(json_file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<ChampionData>(&content).map_err(|e| e.to_string()))
+        {
+            Ok(champion) => champion,
+            Err(e) => {
+                println!("Skipping {}: {}", json_file.display(), e);
+                return Ok(self.champions.remove(champion_dir).is_some());
+            }
+        };
+
+        let mut entries = Vec::new();
+        for skin in champion.skins {
+            if let Some(fantome) = skin.fantome {
+                entries.push(CatalogEntry { skin_id: skin.id, chroma_id: None, fantome });
+            }
+            for chroma in skin.chromas {
+                if let Some(fantome) = chroma.fantome {
+                    entries.push(CatalogEntry { skin_id: skin.id, chroma_id: Some(chroma.id), fantome });
                 }
             }
         }
-        
-        println!("Indexed {} champions in {:?}", self.champion_names.len(), start.elapsed());
-        Ok(())
+        self.champions.insert(champion_dir.to_string(), IndexedChampion {
+            id: champion.id,
+            name: champion.name,
+            alias: champion.alias,
+            json: json_stamp,
+            entries,
+        });
+        Ok(true)
     }
-    
-    // Index all fantome files in a directory structure
-    pub fn index_fantome_files(&mut self, base_dir: &Path) -> Result<(), InjectionError> {
-        println!("Indexing fantome files in {}", base_dir.display());
-        let start = Instant::now();
-        
-        // Clear existing data
+
+    // Apply a change to a single path reported by the watcher
+    pub fn update_path(&mut self, path: &Path) -> Result<bool, InjectionError> {
+        let root = match &self.root {
+            Some(root) => root.clone(),
+            None => return Ok(false),
+        };
+        let rel = match relative_path(&root, path) {
+            Some(rel) if !rel.is_empty() => rel,
+            _ => return Ok(false),
+        };
+
+        if !path.exists() {
+            return Ok(self.forget(&rel));
+        }
+        // New or moved-in folder
+        if path.is_dir() {
+            return self.sync_under(path);
+        }
+        if let Some(champion_dir) = champion_json_dir(&rel) {
+            return self.update_champion(champion_dir, path);
+        }
+        if ModKind::detect(path).is_some() {
+            let new_stamp = stamp(&fs::metadata(path)?);
+            return Ok(self.update_mod_file(normalize_key(&rel), rel, new_stamp));
+        }
+        Ok(false)
+    }
+
+    // Drop everything at or below a path that no longer exists
+    fn forget(&mut self, rel: &str) -> bool {
+        let prefix = normalize_key(rel);
+        let under = |key: &str| key == prefix || key.starts_with(&format!("{}/", prefix));
+        let before = (self.mod_files.len(), self.champions.len());
+        self.mod_files.retain(|key, _| !under(key));
+        self.champions.retain(|dir, _| {
+            let dir = dir.to_lowercase();
+            !under(&dir) && champion_json_dir(&prefix) != Some(dir.as_str())
+        });
+        before != (self.mod_files.len(), self.champions.len())
+    }
+
+    // Derive the lookup tables from champions and mod_files
+    pub fn rebuild_lookups(&mut self) {
+        self.champion_names.clear();
         self.skin_paths.clear();
         self.fantome_by_filename.clear();
-        self.all_fantome_files.clear();
-        
-        if !base_dir.exists() {
-            return Err(InjectionError::IoError(io::Error::new(
-                io::ErrorKind::NotFound, 
-                format!("Base directory not found: {}", base_dir.display())
-            )));
-        }
-        
-        // Walk the directory tree
-        for entry in WalkDir::new(base_dir) {
-            let entry = entry?;
-            let path = entry.path();
-            
-            // Only process .fantome files
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "fantome") {
-                let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                
-                // Add to all_fantome_files list
-                self.all_fantome_files.push((path.to_path_buf(), Instant::now()));
-                
-                // Add to filename index
-                self.fantome_by_filename.insert(file_name.clone(), path.to_path_buf());
-                
-                // Try to extract champion_id and skin_id from path or file name
-                if let Some((champion_id, _skin_id)) = extract_skin_info_from_path(path) {
-                    let key = (champion_id, None);  // No chroma support yet in this simplified version
-                    self.skin_paths.entry(key).or_insert_with(Vec::new).push(path.to_path_buf());
-                }
+        for (dir, champion) in &self.champions {
+            self.champion_names.insert(champion.id, dir.clone());
+            for entry in &champion.entries {
+                self.skin_paths.insert((champion.id, entry.skin_id, entry.chroma_id), entry.fantome.clone());
             }
         }
-        
-        self.last_indexed = Some(Instant::now());
-        println!("Indexed {} fantome files in {:?}", self.all_fantome_files.len(), start.elapsed());
-        
-        Ok(())
+        for key in self.mod_files.keys() {
+            self.fantome_by_filename.insert(key_file_name(key).to_string(), key.clone());
+        }
+    }
+
+    // File on disk for a fantome path from the champion JSON ("aatrox/skin.fantome")
+    fn resolve_fantome(&self, champion_dir: &str, fantome: &str) -> Option<PathBuf> {
+        let root = self.root.as_deref()?;
+        let key = normalize_key(fantome);
+        let file_name = key_file_name(&key);
+        let file = self.mod_files.get(&key)
+            .or_else(|| self.mod_files.get(&format!("{}/{}", champion_dir.to_lowercase(), file_name)))
+            .or_else(|| self.fantome_by_filename.get(file_name).and_then(|k| self.mod_files.get(k)))?;
+        Some(root.join(&file.path))
     }
-    
+
     // Find fantome file for a skin using the indexed data
-    pub fn find_fantome_for_skin(&self, skin: &crate::injection::types::Skin, fantome_files_dir: &Path) -> Option<PathBuf> {
-        // First, check if we have it in our skin paths table
-        let key = (skin.champion_id, skin.chroma_id);
-        
-        if let Some(paths) = self.skin_paths.get(&key) {
-            if !paths.is_empty() {
-                return Some(paths[0].clone());
-            }
+    pub fn find_fantome_for_skin(&self, skin: &Skin) -> Option<PathBuf> {
+        // An absolute path is an explicit pick (custom skins), file or mod folder
+        if let Some(path) = skin.fantome_path.as_deref().map(Path::new).filter(|p| p.is_absolute()) {
+            return path.exists().then(|| path.to_path_buf());
         }
-        
-        // If not found, check direct path from JSON if provided
-        if let Some(fantome_path) = &skin.fantome_path {
-            // Check by direct path
-            let full_path = fantome_files_dir.join(fantome_path);
-            if full_path.exists() {
-                return Some(full_path);
-            }
-            
-            // Check by filename
-            if let Some(filename) = fantome_path.split('/').last() {
-                if let Some(path) = self.fantome_by_filename.get(filename) {
-                    return Some(path.clone());
-                }
+
+        let champion_dir = self.champion_names.get(&skin.champion_id).map(String::as_str).unwrap_or("");
+        if let Some(fantome) = self.skin_paths.get(&(skin.champion_id, skin.skin_id, skin.chroma_id)) {
+            if let Some(path) = self.resolve_fantome(champion_dir, fantome) {
+                return Some(path);
             }
         }
-        
-        // Not found in index
-        None
+
+        // Not in the catalog: a path the JSON doesn't list
+        self.resolve_fantome(champion_dir, skin.fantome_path.as_deref()?)
+    }
+
+    // Champion (id, folder name) by alias, display name or folder name, ignoring case and punctuation
+    pub fn find_champion(&self, name: &str) -> Option<(u32, String)> {
+        let simplify = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+        let wanted = simplify(name);
+        if wanted.is_empty() {
+            return None;
+        }
+        self.champions.iter()
+            .find(|(dir, champion)| {
+                simplify(&champion.alias) == wanted || simplify(&champion.name) == wanted || simplify(dir) == wanted
+            })
+            .map(|(dir, champion)| (champion.id, dir.clone()))
     }
-    
-    #[allow(dead_code)]
+
     // Get champion name, preferring the cached version
     pub fn get_champion_name(&self, champion_id: u32) -> Option<String> {
         self.champion_names.get(&champion_id).cloned()
     }
-    
-    // Check if index needs refresh (older than 5 minutes)
-    pub fn needs_refresh(&self) -> bool {
-        match self.last_indexed {
-            Some(time) => time.elapsed() > Duration::from_secs(5 * 60),
-            None => true,
-        }
-    }
-}
-
-// Function to extract skin information from a file path
-fn extract_skin_info_from_path(path: &Path) -> Option<(u32, u32)> {
-    let file_name = path.file_name()?.to_string_lossy().to_string();
-    
-    // Try to extract skin/champion IDs from filename
-    // Common formats like "Champion_Skin_1234.fantome" or "1234_Champion_Skin.fantome"
-    let parts: Vec<&str> = file_name.split(|c: char| !c.is_alphanumeric()).collect();
-    
-    for part in parts {
-        if let Ok(id) = part.parse::<u32>() {
-            if id > 0 {
-                // Very simplistic approach - assume first numeric part over 0 is either champion or skin ID
-                // In a real implementation, you'd need more sophisticated parsing based on your file naming conventions
-                return Some((id, 0)); // For simplicity, we're defaulting skin_id to 0
-            }
+}
+
+// Global index for champions_dir, loaded from file_index.json the first time
+pub fn global_index_for(champions_dir: &Path) -> Result<Arc<Mutex<FileIndex>>, InjectionError> {
+    let index = GLOBAL_FILE_INDEX.clone();
+    if index.lock().unwrap().root.as_deref() != Some(champions_dir) {
+        // Reading (or on first start building) the index doesn't block lookups
+        let opened = FileIndex::open(champions_dir)?;
+        let mut locked_index = index.lock().unwrap();
+        if locked_index.root.as_deref() != Some(champions_dir) {
+            *locked_index = opened;
         }
     }
-    
-    None
+    Ok(index)
 }
 
-// Function to get or initialize the global index
-pub fn get_global_index(app_handle: &AppHandle) -> Result<Arc<Mutex<FileIndex>>, InjectionError> {
+// Scan dir without holding the index lock, then apply what was found
+fn reconcile(champions_dir: &Path, dir: &Path) -> Result<(), InjectionError> {
+    let scan = match scan_dir(champions_dir, dir)? {
+        Some(scan) => scan,
+        None => return Ok(()),
+    };
     let index = GLOBAL_FILE_INDEX.clone();
-    
-    // Check if we need to initialize the index
-    let needs_init = {
-        let locked_index = index.lock().unwrap();
-        locked_index.champion_names.is_empty() || locked_index.needs_refresh()
+    let mut index = index.lock().unwrap();
+    if index.root.as_deref() != Some(champions_dir) {
+        return Ok(());
+    }
+    if index.apply_scan(scan)? {
+        index.rebuild_lookups();
+        index.save()?;
+    }
+    Ok(())
+}
+
+fn watching(champions_dir: &Path) -> bool {
+    INDEX_WATCHER.lock().unwrap()
+        .as_ref()
+        .map(|(root, _)| root == champions_dir)
+        .unwrap_or(false)
+}
+
+// Look a skin up in the global index
+pub fn lookup_skin(champions_dir: &Path, skin: &Skin) -> Result<Option<PathBuf>, InjectionError> {
+    let watched = watching(champions_dir);
+    let index = global_index_for(champions_dir)?;
+    let (found, champion_dir, recent) = {
+        let index = index.lock().unwrap();
+        let recent = index.last_indexed
+            .map(|time| time.elapsed() < MISS_REFRESH_INTERVAL)
+            .unwrap_or(false);
+        (index.find_fantome_for_skin(skin), index.get_champion_name(skin.champion_id), recent)
     };
-    
-    if needs_init {
-        // Get the champions directory path
-        let app_data_dir = app_handle.path().app_data_dir()
-            .map_err(|e| InjectionError::IoError(io::Error::new(io::ErrorKind::NotFound, format!("{}", e))))?;
-        let champions_dir = app_data_dir.join("champions");
-        
-        // Initialize with locked access
-        let mut locked_index = index.lock().unwrap();
-        locked_index.index_champions(&champions_dir)?;
-        locked_index.index_fantome_files(&champions_dir)?;
+
+    if let Some(path) = found {
+        // Checked outside the lock; a file that vanished without an event is dropped
+        if path.exists() {
+            return Ok(Some(path));
+        }
+        let mut index = index.lock().unwrap();
+        if index.update_path(&path)? {
+            index.rebuild_lookups();
+            let _ = index.save();
+        }
+        return Ok(index.find_fantome_for_skin(skin));
     }
-    
-    Ok(index)
-}
\ No newline at end of file
+
+    // The watcher reports new files; without one, look in the champion's folder once in a while
+    if watched || recent || !champions_dir.exists() {
+        return Ok(None);
+    }
+    let dir = champion_dir.map_or_else(|| champions_dir.to_path_buf(), |name| champions_dir.join(name));
+    reconcile(champions_dir, &dir)?;
+    let index = index.lock().unwrap();
+    Ok(index.find_fantome_for_skin(skin))
+}
+
+// The saved skin of each champion in champion_ids, in that order. Empty slots (0)
+// and champions without a saved skin are left out.
+pub fn skins_for_champions(saved: &[Skin], champion_ids: &[i64]) -> Vec<Skin> {
+    champion_ids.iter()
+        .filter_map(|&id| u32::try_from(id).ok().filter(|&id| id > 0))
+        .filter_map(|id| saved.iter().find(|skin| skin.champion_id == id).cloned())
+        .collect()
+}
+
+fn apply_watch_events(champions_dir: &Path, events: &[DebouncedEvent]) -> Result<(), InjectionError> {
+    // The watcher lost track of what changed
+    if events.iter().any(|event| event.need_rescan()) {
+        return reconcile(champions_dir, champions_dir);
+    }
+
+    // New folders are walked before taking the index lock, like reconcile does
+    let mut seen = HashSet::new();
+    let mut scans = Vec::new();
+    let mut paths = Vec::new();
+    let changed_paths = events.iter()
+        .filter(|event| !matches!(event.kind, EventKind::Access(_)))
+        .flat_map(|event| &event.paths);
+    for path in changed_paths {
+        // Events on the champions folder itself say nothing about its contents
+        if path == champions_dir || !seen.insert(path) {
+            continue;
+        }
+        if !path.is_dir() {
+            paths.push(path);
+            continue;
+        }
+        match scan_dir(champions_dir, path) {
+            Ok(Some(scan)) => scans.push(scan),
+            Ok(None) => {}
+            Err(e) => println!("Failed to scan {}: {}", path.display(), e),
+        }
+    }
+
+    let index = GLOBAL_FILE_INDEX.clone();
+    let mut index = index.lock().unwrap();
+    if index.root.as_deref() != Some(champions_dir) {
+        return Ok(());
+    }
+
+    // One unreadable path doesn't keep the rest of the batch out of the index
+    let mut changed = false;
+    for scan in scans {
+        let prefix = scan.prefix.clone();
+        match index.apply_scan(scan) {
+            Ok(updated) => changed |= updated,
+            Err(e) => println!("Failed to index {}: {}", prefix, e),
+        }
+    }
+    for path in paths {
+        match index.update_path(path) {
+            Ok(updated) => changed |= updated,
+            Err(e) => println!("Failed to index {}: {}", path.display(), e),
+        }
+    }
+
+    if changed {
+        index.rebuild_lookups();
+        index.save()?;
+    }
+    Ok(())
+}
+
+// Load the saved index and keep it current while the app runs. Changes made while the
+// app was closed are picked up by a stat-only scan in the background, which only takes
+// the index lock to apply what it found.
+pub fn start_index_watcher(app_handle: &AppHandle) -> Result<(), InjectionError> {
+    let app_data_dir = app_handle.path().app_data_dir()
+        .map_err(|e| InjectionError::IoError(io::Error::new(io::ErrorKind::NotFound, format!("{}", e))))?;
+    let champions_dir = app_data_dir.join("champions");
+    fs::create_dir_all(&champions_dir)?;
+    global_index_for(&champions_dir)?;
+
+    if !watching(&champions_dir) {
+        let root = champions_dir.clone();
+        let mut debouncer = new_debouncer(WATCH_DEBOUNCE, None, move |result: DebounceEventResult| {
+            match result {
+                Ok(events) => {
+                    if let Err(e) = apply_watch_events(&root, &events) {
+                        println!("Failed to update file index: {}", e);
+                    }
+                }
+                Err(errors) => {
+                    for e in errors {
+                        println!("File index watcher error: {}", e);
+                    }
+                }
+            }
+        }).map_err(|e| InjectionError::ProcessError(format!("Failed to start file index watcher: {}", e)))?;
+        debouncer.watch(&champions_dir, RecursiveMode::Recursive)
+            .map_err(|e| InjectionError::ProcessError(format!("Failed to watch {}: {}", champions_dir.display(), e)))?;
+        *INDEX_WATCHER.lock().unwrap() = Some((champions_dir.clone(), debouncer));
+    }
+
+    std::thread::spawn(move || {
+        if let Err(e) = reconcile(&champions_dir, &champions_dir) {
+            println!("Failed to sync file index: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skin(skin_id: u32) -> Skin {
+        Skin { champion_id: 103, skin_id, chroma_id: None, fantome_path: None }
+    }
+
+    #[test]
+    fn scan_picks_up_added_and_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let champions_dir = dir.path().join("champions");
+        let ahri = champions_dir.join("Ahri");
+        fs::create_dir_all(&ahri).unwrap();
+        fs::write(ahri.join("Ahri.json"), r#"{"id": 103, "name": "Ahri", "alias": "Ahri", "skins": [
+            {"id": 1, "fantome": "ahri/arcade.fantome"},
+            {"id": 2, "fantome": "ahri/star_guardian.fantome"}
+        ]}"#).unwrap();
+        fs::write(ahri.join("arcade.fantome"), b"arcade").unwrap();
+
+        let mut index = FileIndex::new();
+        index.build(&champions_dir).unwrap();
+        assert_eq!(index.find_fantome_for_skin(&skin(1)), Some(ahri.join("arcade.fantome")));
+        assert_eq!(index.find_fantome_for_skin(&skin(2)), None);
+
+        fs::write(ahri.join("star_guardian.fantome"), b"star guardian").unwrap();
+        fs::remove_file(ahri.join("arcade.fantome")).unwrap();
+        let scan = scan_dir(&champions_dir, &ahri).unwrap().unwrap();
+        assert!(index.apply_scan(scan).unwrap());
+        index.rebuild_lookups();
+        assert_eq!(index.find_fantome_for_skin(&skin(1)), None);
+        assert_eq!(index.find_fantome_for_skin(&skin(2)), Some(ahri.join("star_guardian.fantome")));
+        assert_eq!(index.get_champion_name(103).as_deref(), Some("Ahri"));
+    }
+}
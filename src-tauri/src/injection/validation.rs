@@ -0,0 +1,563 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use zip::read::ZipFile;
+use zip::ZipArchive;
+
+use crate::injection::error::InjectionError;
+use crate::injection::normalize::{self, is_wad_name, ModKind};
+use crate::injection::wad::{self, WadArchive};
+
+// Validation of mod inputs before anything is extracted
+// For archives only the zip central directory is read, plus META/info.json and the
+// first bytes of each packed WAD, so checking a huge or malicious archive stays cheap.
+
+pub const MAX_ENTRIES: usize = 50_000;
+pub const MAX_TOTAL_UNCOMPRESSED: u64 = 4 * 1024 * 1024 * 1024;
+pub const MAX_ENTRY_UNCOMPRESSED: u64 = 2 * 1024 * 1024 * 1024;
+// Only checked for entries over 1 MiB, tiny files compress extremely well legitimately
+pub const MAX_COMPRESSION_RATIO: u64 = 100;
+const RATIO_MIN_SIZE: u64 = 1024 * 1024;
+const MAX_INFO_JSON_SIZE: u64 = 64 * 1024;
+// Extraction checks for cancellation between chunks of this size
+const COPY_CHUNK_SIZE: usize = 256 * 1024;
+
+// META/info.json as written by cslol-manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FantomeInfo {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Author", default)]
+    pub author: String,
+    #[serde(rename = "Version", default)]
+    pub version: String,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FantomeReport {
+    pub path: String,
+    pub kind: Option<ModKind>,
+    pub valid: bool,
+    pub info: Option<FantomeInfo>,
+    pub entry_count: usize,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    pub wads: Vec<String>, // WAD names found under WAD/, packed or as folders
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl FantomeReport {
+    // Turn a report with errors into an InvalidFantome error
+    pub fn into_result(self) -> Result<Self, InjectionError> {
+        if self.valid {
+            Ok(self)
+        } else {
+            Err(InjectionError::InvalidFantome(format!("{}: {}", self.path, self.errors.join("; "))))
+        }
+    }
+}
+
+fn is_symlink(file: &ZipFile) -> bool {
+    file.unix_mode().map(|mode| mode & 0o170000 == 0o120000).unwrap_or(false)
+}
+
+// Validate any supported mod input: .fantome, .zip, bare WAD or unpacked folder.
+// Err is only returned when the input can't be read at all; everything wrong with
+// its contents ends up in the report.
+pub fn validate_mod(path: &Path) -> Result<FantomeReport, InjectionError> {
+    match ModKind::detect(path) {
+        Some(ModKind::Fantome) => validate_archive(path, ModKind::Fantome),
+        Some(ModKind::Zip) => validate_archive(path, ModKind::Zip),
+        Some(ModKind::Wad) => validate_wad_file(path),
+        Some(ModKind::Folder) => validate_folder(path),
+        None => {
+            let mut report = new_report(path, None);
+            report.errors.push("Unsupported file type, expected .fantome, .zip, .wad.client or a folder".into());
+            Ok(report)
+        }
+    }
+}
+
+fn new_report(path: &Path, kind: Option<ModKind>) -> FantomeReport {
+    FantomeReport {
+        path: path.to_string_lossy().to_string(),
+        kind,
+        ..Default::default()
+    }
+}
+
+// Fantomes must follow the META/ + WAD/ layout; plain zips may keep their WADs
+// anywhere and get an info.json generated when installed
+fn validate_archive(path: &Path, kind: ModKind) -> Result<FantomeReport, InjectionError> {
+    let strict = kind == ModKind::Fantome;
+    let mut report = new_report(path, Some(kind));
+    let mut archive = match ZipArchive::new(File::open(path)?) {
+        Ok(archive) => archive,
+        Err(e) => {
+            report.errors.push(format!("Not a valid zip archive: {}", e));
+            return Ok(report);
+        }
+    };
+
+    report.entry_count = archive.len();
+    if archive.len() > MAX_ENTRIES {
+        report.errors.push(format!("Too many entries ({}, limit {})", archive.len(), MAX_ENTRIES));
+        return Ok(report);
+    }
+
+    let mut info_index = None;
+    let mut packed_wads = Vec::new();
+    let mut wads = BTreeSet::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        let name = file.name().replace('\\', "/");
+        report.compressed_size += file.compressed_size();
+        report.uncompressed_size = report.uncompressed_size.saturating_add(file.size());
+
+        if file.enclosed_name().is_none() {
+            report.errors.push(format!("Unsafe path: {}", name));
+            continue;
+        }
+        if is_symlink(&file) {
+            report.errors.push(format!("Symbolic links are not allowed: {}", name));
+            continue;
+        }
+        if file.size() > MAX_ENTRY_UNCOMPRESSED {
+            report.errors.push(format!("{} is too large ({} bytes)", name, file.size()));
+        }
+        if file.size() > RATIO_MIN_SIZE && file.size() / file.compressed_size().max(1) > MAX_COMPRESSION_RATIO {
+            report.errors.push(format!(
+                "{} has a suspicious compression ratio ({} -> {} bytes)",
+                name, file.compressed_size(), file.size()
+            ));
+        }
+        if file.is_dir() {
+            continue;
+        }
+
+        let lower = name.to_lowercase();
+        let mut parts = name.splitn(3, '/');
+        match (parts.next(), parts.next(), parts.next()) {
+            _ if lower == "meta/info.json" => info_index = Some(i),
+            (Some(top), _, _) if top.eq_ignore_ascii_case("META") => {}
+            (Some(top), _, _) if top.eq_ignore_ascii_case("RAW") => {}
+            (Some(top), Some(wad_name), None) if top.eq_ignore_ascii_case("WAD") && is_wad_name(wad_name) => {
+                wads.insert(wad_name.to_string());
+                packed_wads.push(i);
+            }
+            (Some(top), Some(wad_name), Some(_)) if top.eq_ignore_ascii_case("WAD") && is_wad_name(wad_name) => {
+                wads.insert(wad_name.to_string());
+            }
+            (Some(top), _, _) if top.eq_ignore_ascii_case("WAD") => {
+                report.errors.push(format!("Unexpected file in WAD/: {}", name));
+            }
+            _ if !strict => {
+                // The first WAD-named component decides: last one is a packed WAD, otherwise a WAD folder
+                let components: Vec<&str> = name.split('/').collect();
+                match components.iter().position(|part| is_wad_name(part)) {
+                    Some(pos) => {
+                        wads.insert(components[pos].to_string());
+                        if pos == components.len() - 1 {
+                            packed_wads.push(i);
+                        }
+                    }
+                    None => report.warnings.push(format!("Ignoring file that is not part of a WAD: {}", name)),
+                }
+            }
+            _ => report.warnings.push(format!("Ignoring file outside META/, WAD/ and RAW/: {}", name)),
+        }
+    }
+    if report.uncompressed_size > MAX_TOTAL_UNCOMPRESSED {
+        report.errors.push(format!(
+            "Archive expands to {} bytes (limit {})", report.uncompressed_size, MAX_TOTAL_UNCOMPRESSED
+        ));
+    }
+    report.wads = wads.into_iter().collect();
+    if report.wads.is_empty() {
+        report.errors.push(if strict { "No WAD files under WAD/".into() } else { "No WAD files in the archive".to_string() });
+    }
+
+    // Packed WADs must at least look like WADs
+    for i in packed_wads {
+        let mut file = archive.by_index(i)?;
+        let mut header = Vec::with_capacity(4);
+        (&mut file).take(4).read_to_end(&mut header)?;
+        if !wad::is_wad_header(&header) {
+            report.errors.push(format!("{} is not a WAD file", file.name()));
+        }
+    }
+
+    match info_index {
+        Some(i) => {
+            let file = archive.by_index(i)?;
+            if file.size() > MAX_INFO_JSON_SIZE {
+                report.errors.push(format!("META/info.json is too large ({} bytes)", file.size()));
+            } else {
+                let mut content = String::new();
+                match file.take(MAX_INFO_JSON_SIZE).read_to_string(&mut content) {
+                    Ok(_) => match check_info_json(&content) {
+                        Ok(info) => report.info = Some(info),
+                        Err(e) => report.errors.push(format!("META/info.json: {}", e)),
+                    },
+                    Err(e) => report.errors.push(format!("META/info.json is unreadable: {}", e)),
+                }
+            }
+        }
+        None if strict => report.errors.push("Missing META/info.json".into()),
+        None => report.warnings.push("No META/info.json, one will be generated".into()),
+    }
+
+    report.valid = report.errors.is_empty();
+    Ok(report)
+}
+
+// A bare WAD has to parse; the TOC is all that is read
+fn validate_wad_file(path: &Path) -> Result<FantomeReport, InjectionError> {
+    let mut report = new_report(path, Some(ModKind::Wad));
+    let size = std::fs::metadata(path)?.len();
+    report.compressed_size = size;
+    report.uncompressed_size = size;
+    report.wads.push(path.file_name().unwrap_or_default().to_string_lossy().to_string());
+    match WadArchive::read_from_file(path) {
+        Ok(archive) if archive.entries.is_empty() => report.errors.push("WAD file has no entries".into()),
+        Ok(archive) => report.entry_count = archive.entries.len(),
+        Err(e) => report.errors.push(format!("Not a valid WAD file: {}", e)),
+    }
+    report.valid = report.errors.is_empty();
+    Ok(report)
+}
+
+// Unpacked mod folder: no links out of the folder, at least one WAD, sane info.json
+fn validate_folder(path: &Path) -> Result<FantomeReport, InjectionError> {
+    let mut report = new_report(path, Some(ModKind::Folder));
+    for entry in walkdir::WalkDir::new(path).min_depth(1) {
+        let entry = entry?;
+        if entry.path_is_symlink() {
+            report.errors.push(format!("Symbolic links are not allowed: {}", entry.path().display()));
+        } else if entry.file_type().is_file() {
+            report.entry_count += 1;
+            report.uncompressed_size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    report.compressed_size = report.uncompressed_size;
+    if report.entry_count > MAX_ENTRIES {
+        report.errors.push(format!("Too many files ({}, limit {})", report.entry_count, MAX_ENTRIES));
+    }
+
+    report.wads = normalize::find_wads(path)?.into_iter().map(|(name, _)| name).collect();
+    if report.wads.is_empty() && !path.join("RAW").is_dir() {
+        report.errors.push("No WAD files in the folder".into());
+    }
+
+    let info_path = path.join("META").join("info.json");
+    if info_path.is_file() {
+        match std::fs::read_to_string(&info_path).map_err(|e| e.to_string()).and_then(|c| check_info_json(&c)) {
+            Ok(info) => report.info = Some(info),
+            Err(e) => report.errors.push(format!("META/info.json: {}", e)),
+        }
+    } else {
+        report.warnings.push("No META/info.json, one will be generated".into());
+    }
+
+    report.valid = report.errors.is_empty();
+    Ok(report)
+}
+
+// Name is required, the other known fields must be strings when present
+fn check_info_json(content: &str) -> Result<FantomeInfo, String> {
+    // Some tools write a UTF-8 BOM
+    let content = content.trim_start_matches('\u{feff}');
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("invalid JSON: {}", e))?;
+    let object = value.as_object().ok_or("expected a JSON object")?;
+    for field in ["Author", "Version", "Description"] {
+        match object.get(field) {
+            None | Some(serde_json::Value::String(_)) => {}
+            Some(_) => return Err(format!("\"{}\" must be a string", field)),
+        }
+    }
+    match object.get("Name") {
+        Some(serde_json::Value::String(name)) if !name.trim().is_empty() => {}
+        Some(serde_json::Value::String(_)) => return Err("\"Name\" is empty".into()),
+        Some(_) => return Err("\"Name\" must be a string".into()),
+        None => return Err("\"Name\" is missing".into()),
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+// Extract one entry, failing if it inflates past the size its header declared.
+// checkpoint runs between chunks so a cancelled job doesn't finish a huge file first.
+pub fn copy_entry<W: Write>(
+    file: &mut ZipFile,
+    out: &mut W,
+    checkpoint: &dyn Fn() -> Result<(), InjectionError>,
+) -> Result<u64, InjectionError> {
+    let declared = file.size();
+    let name = file.name().to_string();
+    let mut limited = (&mut *file).take(declared + 1);
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    let mut written: u64 = 0;
+    loop {
+        checkpoint()?;
+        let read = match limited.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        out.write_all(&buffer[..read])?;
+        written += read as u64;
+    }
+    if written > declared {
+        return Err(InjectionError::InvalidFantome(format!(
+            "{} is larger than declared in the archive", name
+        )));
+    }
+    Ok(written)
+}
+
+// Whether a zip is a pack of mods (.fantome or .zip files, or mod folders) rather than a mod itself
+pub fn zip_contains_mods(path: &Path) -> Result<bool, InjectionError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    for i in 0..archive.len() {
+        let name = archive.by_index_raw(i)?.name().replace('\\', "/").to_lowercase();
+        if name.ends_with(".fantome") || name.ends_with(".zip") || name.ends_with("/meta/info.json") {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Extract an archive of mods (e.g. a zip of .fantome files) under the same limits as a mod.
+// Its contents are validated one by one afterwards, this only keeps the extraction itself safe.
+pub fn extract_zip(path: &Path, out_dir: &Path) -> Result<(), InjectionError> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    if archive.len() > MAX_ENTRIES {
+        return Err(InjectionError::InvalidFantome(format!(
+            "{} has too many entries ({}, limit {})", path.display(), archive.len(), MAX_ENTRIES
+        )));
+    }
+
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let rel_path = file.enclosed_name()
+            .map(|p| p.to_path_buf())
+            .ok_or_else(|| InjectionError::InvalidFantome(format!("Unsafe path: {}", file.name())))?;
+        if is_symlink(&file) {
+            return Err(InjectionError::InvalidFantome(format!("Symbolic links are not allowed: {}", file.name())));
+        }
+        total = total.saturating_add(file.size());
+        if file.size() > MAX_ENTRY_UNCOMPRESSED || total > MAX_TOTAL_UNCOMPRESSED {
+            return Err(InjectionError::InvalidFantome(format!("{} is too large to extract", path.display())));
+        }
+
+        let target = out_dir.join(rel_path);
+        if file.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = File::create(&target)?;
+        copy_entry(&mut file, &mut out, &|| Ok(()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::io::Cursor;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    use super::*;
+
+    fn archive_with(size: usize) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("WAD/Ahri.wad.client", FileOptions::default()).unwrap();
+        zip.write_all(&vec![7u8; size]).unwrap();
+        ZipArchive::new(Cursor::new(zip.finish().unwrap().into_inner())).unwrap()
+    }
+
+    #[test]
+    fn copy_entry_stops_when_cancelled() {
+        let size = COPY_CHUNK_SIZE * 8;
+        let mut archive = archive_with(size);
+
+        let mut out = Vec::new();
+        let written = copy_entry(&mut archive.by_index(0).unwrap(), &mut out, &|| Ok(())).unwrap();
+        assert_eq!((written, out.len()), (size as u64, size));
+
+        // Cancelled after the second chunk
+        let chunks = Cell::new(0);
+        let checkpoint = || {
+            chunks.set(chunks.get() + 1);
+            if chunks.get() > 2 {
+                Err(InjectionError::Aborted("cancelled".into()))
+            } else {
+                Ok(())
+            }
+        };
+        let mut out = Vec::new();
+        let result = copy_entry(&mut archive.by_index(0).unwrap(), &mut out, &checkpoint);
+        assert!(matches!(result, Err(InjectionError::Aborted(_))));
+        assert!(out.len() < size);
+    }
+
+    const WAD: &[u8] = b"RW\x03\x00";
+
+    fn zip_of(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            zip.start_file(*name, FileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    // Pretend every entry expands to size bytes. Only the central directory is
+    // consulted for sizes, so the limits can be hit without allocating gigabytes.
+    fn claim_uncompressed(mut bytes: Vec<u8>, size: u32) -> Vec<u8> {
+        let mut i = 0;
+        while i + 28 <= bytes.len() {
+            if bytes[i..i + 4] == [0x50, 0x4b, 0x01, 0x02] {
+                bytes[i + 24..i + 28].copy_from_slice(&size.to_le_bytes());
+            }
+            i += 1;
+        }
+        bytes
+    }
+
+    fn validate_fantome(bytes: &[u8]) -> FantomeReport {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mod.fantome");
+        std::fs::write(&path, bytes).unwrap();
+        validate_mod(&path).unwrap()
+    }
+
+    fn assert_rejected(report: &FantomeReport, error: &str) {
+        assert!(!report.valid);
+        assert!(
+            report.errors.iter().any(|e| e.contains(error)),
+            "expected an error containing {:?}, got {:?}", error, report.errors
+        );
+    }
+
+    #[test]
+    fn accepts_a_well_formed_fantome() {
+        let report = validate_fantome(&zip_of(&[
+            ("META/info.json", br#"{"Name": "Arcade Ahri", "Author": "me"}"#),
+            ("WAD/Ahri.wad.client", WAD),
+        ]));
+        assert!(report.valid, "{:?}", report.errors);
+        assert_eq!(report.info.unwrap().name, "Arcade Ahri");
+        assert_eq!(report.wads, vec!["Ahri.wad.client"]);
+    }
+
+    #[test]
+    fn rejects_too_many_entries() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for i in 0..=MAX_ENTRIES {
+            zip.start_file(format!("RAW/{}", i), FileOptions::default()).unwrap();
+        }
+        let report = validate_fantome(&zip.finish().unwrap().into_inner());
+        assert_eq!(report.entry_count, MAX_ENTRIES + 1);
+        assert_rejected(&report, "Too many entries");
+    }
+
+    #[test]
+    fn rejects_oversized_entries_and_archives() {
+        let bytes = claim_uncompressed(zip_of(&[("WAD/Ahri.wad.client", WAD)]), 3_000_000_000);
+        assert_rejected(&validate_fantome(&bytes), "Ahri.wad.client is too large");
+
+        // Each entry is under the per-entry limit, together they are not
+        let wads = [
+            ("WAD/Ahri.wad.client", WAD),
+            ("WAD/Lux.wad.client", WAD),
+            ("WAD/Zed.wad.client", WAD),
+        ];
+        let report = validate_fantome(&claim_uncompressed(zip_of(&wads), 1_500_000_000));
+        assert!(!report.errors.iter().any(|e| e.contains("is too large")), "{:?}", report.errors);
+        assert_rejected(&report, "Archive expands to 4500000000 bytes");
+    }
+
+    #[test]
+    fn rejects_zip_bombs() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let deflated = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        zip.start_file("WAD/Ahri.wad.client", deflated).unwrap();
+        zip.write_all(WAD).unwrap();
+        zip.write_all(&vec![0u8; 4 * RATIO_MIN_SIZE as usize]).unwrap();
+        let report = validate_fantome(&zip.finish().unwrap().into_inner());
+        assert_rejected(&report, "suspicious compression ratio");
+    }
+
+    #[test]
+    fn rejects_symlinks() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("WAD/Ahri.wad.client", FileOptions::default()).unwrap();
+        zip.write_all(WAD).unwrap();
+        zip.add_symlink("WAD/Lux.wad.client", "/etc/passwd", FileOptions::default()).unwrap();
+        let report = validate_fantome(&zip.finish().unwrap().into_inner());
+        assert_rejected(&report, "Symbolic links are not allowed: WAD/Lux.wad.client");
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_archive() {
+        for name in ["../WAD/Ahri.wad.client", "WAD/../../Ahri.wad.client", "/WAD/Ahri.wad.client"] {
+            let report = validate_fantome(&zip_of(&[("WAD/Lux.wad.client", WAD), (name, WAD)]));
+            assert_rejected(&report, &format!("Unsafe path: {}", name));
+        }
+    }
+
+    #[test]
+    fn checks_info_json() {
+        let with_info = |info: &[u8]| {
+            validate_fantome(&zip_of(&[("META/info.json", info), ("WAD/Ahri.wad.client", WAD)]))
+        };
+
+        let description = "x".repeat(MAX_INFO_JSON_SIZE as usize);
+        let padded = format!(r#"{{"Name": "Ahri", "Description": "{}"}}"#, description);
+        assert_rejected(&with_info(padded.as_bytes()), "META/info.json is too large");
+        assert_rejected(&with_info(b"{\"Name\": "), "META/info.json: invalid JSON");
+        assert_rejected(&with_info(b"[]"), "META/info.json: expected a JSON object");
+        assert_rejected(&with_info(br#"{"Author": "me"}"#), r#""Name" is missing"#);
+        assert_rejected(&with_info(br#"{"Name": "  "}"#), r#""Name" is empty"#);
+        assert_rejected(&with_info(br#"{"Name": 7}"#), r#""Name" must be a string"#);
+        assert_rejected(&with_info(br#"{"Name": "Ahri", "Version": 2}"#), r#""Version" must be a string"#);
+
+        // A BOM in front is tolerated
+        assert!(with_info("\u{feff}{\"Name\": \"Ahri\"}".as_bytes()).valid);
+    }
+
+    #[test]
+    fn tells_mod_packs_from_broken_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let write_zip = |name: &str, entries: &[&str]| {
+            let path = dir.path().join(name);
+            let mut zip = ZipWriter::new(File::create(&path).unwrap());
+            for entry in entries {
+                zip.start_file(*entry, FileOptions::default()).unwrap();
+                zip.write_all(b"data").unwrap();
+            }
+            zip.finish().unwrap();
+            path
+        };
+
+        let pack = write_zip("pack.zip", &["Ahri Arcade.fantome", "Lux/META/info.json", "Lux/WAD/Lux.wad.client"]);
+        assert!(!validate_mod(&pack).unwrap().valid);
+        assert!(zip_contains_mods(&pack).unwrap());
+
+        let broken = write_zip("broken.zip", &["readme.txt"]);
+        assert!(!validate_mod(&broken).unwrap().valid);
+        assert!(!zip_contains_mods(&broken).unwrap());
+    }
+}
@@ -0,0 +1,689 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use flate2::read::GzDecoder;
+use memmap2::{Mmap, MmapOptions};
+use walkdir::WalkDir;
+use xxhash_rust::xxh3::{xxh3_64, Xxh3};
+use xxhash_rust::xxh64::xxh64;
+
+use crate::injection::error::InjectionError;
+
+// Native WAD (.wad.client) support - a port of cslol-tools' lol::wad module
+// Layout reference: cslol-manager/cslol-tools/lib/lol/wad/toc.hpp
+
+const WAD_MAGIC: [u8; 2] = *b"RW";
+const LATEST_MAJOR: u8 = 3;
+const LATEST_MINOR: u8 = 4;
+const HEADER_V1_SIZE: usize = 12;
+const HEADER_V2_SIZE: usize = 104;
+const HEADER_V3_SIZE: usize = 272;
+const ENTRY_V1_SIZE: usize = 24;
+const ENTRY_V3_SIZE: usize = 32;
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const MAX_WAD_OFFSET: u64 = u32::MAX as u64;
+
+// Entry storage type, matches lol::wad::EntryType
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    Raw,
+    Link,
+    Gzip,
+    Zstd,
+    ZstdMulti,
+}
+
+impl EntryType {
+    fn from_u8(value: u8) -> Result<Self, InjectionError> {
+        match value {
+            0 => Ok(Self::Raw),
+            1 => Ok(Self::Link),
+            2 => Ok(Self::Gzip),
+            3 => Ok(Self::Zstd),
+            4 => Ok(Self::ZstdMulti),
+            other => Err(InjectionError::WadError(format!("Unknown entry type: {:#x}", other))),
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Raw => 0,
+            Self::Link => 1,
+            Self::Gzip => 2,
+            Self::Zstd => 3,
+            Self::ZstdMulti => 4,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Link => "link",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::ZstdMulti => "zstd-multi",
+        }
+    }
+}
+
+// Bytes backing an entry - either a slice of a memory-mapped WAD or an owned buffer
+#[derive(Clone)]
+enum Backing {
+    Mapped(Arc<Mmap>),
+    Owned(Arc<Vec<u8>>),
+}
+
+#[derive(Clone)]
+pub struct EntryData {
+    backing: Backing,
+    offset: usize,
+    size: usize,
+}
+
+impl EntryData {
+    pub fn from_vec(bytes: Vec<u8>) -> Self {
+        let size = bytes.len();
+        Self { backing: Backing::Owned(Arc::new(bytes)), offset: 0, size }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        let all: &[u8] = match &self.backing {
+            Backing::Mapped(mmap) => &mmap[..],
+            Backing::Owned(vec) => &vec[..],
+        };
+        &all[self.offset..self.offset + self.size]
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+}
+
+impl std::fmt::Debug for EntryData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EntryData({} bytes)", self.size)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WadEntry {
+    pub entry_type: EntryType,
+    pub subchunk_count: u8,
+    pub subchunk_index: u32,
+    pub size_decompressed: u64,
+    pub checksum: u64,
+    pub data: EntryData,
+    // Game entries are already stored the way the game expects them
+    pub optimal: bool,
+}
+
+impl WadEntry {
+    // Uncompressed file contents, e.g. a file packed from a mod folder
+    pub fn from_raw(bytes: Vec<u8>) -> Self {
+        let checksum = xxh3_64(&bytes);
+        Self {
+            entry_type: EntryType::Raw,
+            subchunk_count: 0,
+            subchunk_index: 0,
+            size_decompressed: bytes.len() as u64,
+            checksum,
+            data: EntryData::from_vec(bytes),
+            optimal: false,
+        }
+    }
+
+    fn from_zstd(bytes: Vec<u8>, size_decompressed: u64) -> Self {
+        let checksum = xxh3_64(&bytes);
+        Self {
+            entry_type: EntryType::Zstd,
+            subchunk_count: 0,
+            subchunk_index: 0,
+            size_decompressed,
+            checksum,
+            data: EntryData::from_vec(bytes),
+            optimal: true,
+        }
+    }
+
+    // Decompress the entry into its original file contents
+    pub fn decompressed(&self) -> Result<Vec<u8>, InjectionError> {
+        let src = self.data.bytes();
+        match self.entry_type {
+            EntryType::Raw | EntryType::Link => Ok(src.to_vec()),
+            EntryType::Gzip => {
+                let mut out = Vec::with_capacity(self.size_decompressed as usize);
+                GzDecoder::new(src).read_to_end(&mut out)?;
+                Ok(out)
+            }
+            EntryType::Zstd => zstd::stream::decode_all(src).map_err(InjectionError::from),
+            EntryType::ZstdMulti => {
+                // Subchunked entries may start with an uncompressed prefix before the first zstd frame
+                let frame_start = src.windows(ZSTD_MAGIC.len())
+                    .position(|w| w == ZSTD_MAGIC)
+                    .unwrap_or(src.len());
+                let mut out = src[..frame_start].to_vec();
+                if frame_start < src.len() {
+                    out.extend(zstd::stream::decode_all(&src[frame_start..])?);
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    // First few bytes of the decompressed contents, enough to sniff the file type
+    pub fn magic(&self) -> Vec<u8> {
+        let src = self.data.bytes();
+        let mut buf = [0u8; 8];
+        let read = match self.entry_type {
+            EntryType::Raw | EntryType::Link => return src.iter().take(8).cloned().collect(),
+            EntryType::Gzip => GzDecoder::new(src).read(&mut buf).unwrap_or(0),
+            EntryType::Zstd => zstd::stream::read::Decoder::new(src)
+                .and_then(|mut d| d.read(&mut buf))
+                .unwrap_or(0),
+            EntryType::ZstdMulti => return self.decompressed()
+                .map(|d| d.into_iter().take(8).collect())
+                .unwrap_or_default(),
+        };
+        buf[..read].to_vec()
+    }
+
+    // Convert into the storage the game expects: zstd for everything except audio banks
+    pub fn to_optimal(&self) -> Result<WadEntry, InjectionError> {
+        if self.optimal || self.entry_type == EntryType::Link {
+            return Ok(self.clone());
+        }
+        let is_audio = is_audio_bank(&self.magic());
+        match self.entry_type {
+            EntryType::Zstd if !is_audio => {
+                let mut entry = self.clone();
+                entry.optimal = true;
+                Ok(entry)
+            }
+            EntryType::Raw if is_audio => {
+                let mut entry = self.clone();
+                entry.optimal = true;
+                Ok(entry)
+            }
+            _ => {
+                let raw = self.decompressed()?;
+                if is_audio {
+                    let mut entry = WadEntry::from_raw(raw);
+                    entry.optimal = true;
+                    Ok(entry)
+                } else {
+                    let size = raw.len() as u64;
+                    let compressed = zstd::stream::encode_all(&raw[..], 0)?;
+                    Ok(WadEntry::from_zstd(compressed, size))
+                }
+            }
+        }
+    }
+}
+
+// .bnk and .wpk files must stay uncompressed, the game streams them directly
+fn is_audio_bank(magic: &[u8]) -> bool {
+    if magic.starts_with(b"BKHD") {
+        return true;
+    }
+    if magic.starts_with(b"r3d2") {
+        let kind = magic.get(4..8).unwrap_or(&[]);
+        let other_r3d2 = [&b"Mesh"[..], b"aims", b"anmd", b"canm", b"sklt", b"blnd", b"wght"];
+        return !other_r3d2.contains(&kind);
+    }
+    false
+}
+
+// Hash a WAD path the same way the game does (lowercase xxh64)
+// File names that are already a 16 digit hex hash are taken as-is
+pub fn hash_path(path: &str) -> u64 {
+    let trimmed = path.trim_start_matches(['.', '/']);
+    let stem = trimmed.split('.').next().unwrap_or("");
+    if stem.len() == 16 {
+        if let Ok(value) = u64::from_str_radix(stem, 16) {
+            return value;
+        }
+    }
+    xxh64(trimmed.to_lowercase().as_bytes(), 0)
+}
+
+// Mount name of a WAD, e.g. "DATA/FINAL/Champions/Ahri.wad.client" -> "ahri"
+pub fn mount_name(path: &Path) -> String {
+    let mut name = path.file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if let Some(stripped) = name.strip_suffix(".client") {
+        name = stripped.to_string();
+    }
+    if let Some(stripped) = name.strip_suffix(".wad") {
+        name = stripped.to_string();
+    }
+    name
+}
+
+// Relative path with forward slashes, as stored in WAD path hashes
+pub fn generic_relative_path(path: &Path, base: &Path) -> Option<String> {
+    let rel = path.strip_prefix(base).ok()?;
+    let parts: Vec<String> = rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    Some(parts.join("/"))
+}
+
+fn read_u16(src: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([src[pos], src[pos + 1]])
+}
+
+fn read_u32(src: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([src[pos], src[pos + 1], src[pos + 2], src[pos + 3]])
+}
+
+fn read_u64(src: &[u8], pos: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&src[pos..pos + 8]);
+    u64::from_le_bytes(buf)
+}
+
+// A single table-of-contents record, independent of WAD version
+#[derive(Debug, Clone, Copy)]
+pub struct TocEntry {
+    pub name: u64,
+    pub entry_type: EntryType,
+    pub subchunk_count: u8,
+    pub subchunk_index: u32,
+    pub offset: u64,
+    pub size: u64,
+    pub size_decompressed: u64,
+    pub checksum: u64,
+}
+
+// Cheap check on the first bytes of a file: WAD magic and a known major version
+pub fn is_wad_header(header: &[u8]) -> bool {
+    header.len() >= 4 && header[..2] == WAD_MAGIC && header[2] <= LATEST_MAJOR
+}
+
+#[derive(Debug, Clone)]
+pub struct Toc {
+    pub major: u8,
+    pub minor: u8,
+    pub entries: Vec<TocEntry>,
+}
+
+impl Toc {
+    pub fn is_latest(&self) -> bool {
+        self.major == LATEST_MAJOR && self.minor == LATEST_MINOR
+    }
+
+    // Parse a WAD table of contents. Returns None for empty/zeroed placeholder files.
+    pub fn read(src: &[u8]) -> Result<Option<Self>, InjectionError> {
+        if src.len() < 4 || src[..4] == [0, 0, 0, 0] {
+            return Ok(None);
+        }
+        if src[..2] != WAD_MAGIC {
+            return Err(InjectionError::WadError("Bad WAD magic".into()));
+        }
+        let major = src[2];
+        let minor = src[3];
+
+        // (desc_offset, desc_size, desc_count) per header version
+        let (header_size, desc_offset, desc_size, count_pos) = match major {
+            0 | 1 => (HEADER_V1_SIZE, None, ENTRY_V1_SIZE, 8),
+            2 => (HEADER_V2_SIZE, None, ENTRY_V1_SIZE, 100),
+            3 => (HEADER_V3_SIZE, Some(HEADER_V3_SIZE), ENTRY_V3_SIZE, 268),
+            other => return Err(InjectionError::WadError(format!("Bad WAD major version: {}", other))),
+        };
+        if src.len() < header_size {
+            return Err(InjectionError::WadError("Not enough data for header".into()));
+        }
+        let (desc_offset, declared_size) = match desc_offset {
+            Some(offset) => (offset, desc_size),
+            None => (read_u16(src, count_pos - 4) as usize, read_u16(src, count_pos - 2) as usize),
+        };
+        if declared_size != desc_size {
+            return Err(InjectionError::WadError("Bad header desc_size".into()));
+        }
+        let desc_count = read_u32(src, count_pos) as usize;
+        if src.len() < desc_offset || (src.len() - desc_offset) / desc_size < desc_count {
+            return Err(InjectionError::WadError("Not enough data for TOC".into()));
+        }
+
+        let mut entries = Vec::with_capacity(desc_count);
+        for i in 0..desc_count {
+            let pos = desc_offset + desc_size * i;
+            let raw = &src[pos..pos + desc_size];
+            let type_byte = raw[20];
+            let (entry_type, subchunk_count, subchunk_index, checksum) = match (major, minor) {
+                (0, _) | (1, _) => (EntryType::from_u8(type_byte)?, 0, 0, 0),
+                (2, _) | (3, 0) => (EntryType::from_u8(type_byte & 0x0F)?, type_byte >> 4, read_u16(raw, 22) as u32, 0),
+                (3, 1..=3) => (EntryType::from_u8(type_byte & 0x0F)?, type_byte >> 4, read_u16(raw, 22) as u32, read_u64(raw, 24)),
+                _ => {
+                    // Mixed-endian uint24: hi, lo, mi
+                    let index = ((raw[21] as u32) << 16) | ((raw[23] as u32) << 8) | raw[22] as u32;
+                    (EntryType::from_u8(type_byte & 0x0F)?, type_byte >> 4, index, read_u64(raw, 24))
+                }
+            };
+            let entry = TocEntry {
+                name: read_u64(raw, 0),
+                entry_type,
+                subchunk_count,
+                subchunk_index,
+                offset: read_u32(raw, 8) as u64,
+                size: read_u32(raw, 12) as u64,
+                size_decompressed: read_u32(raw, 16) as u64,
+                checksum,
+            };
+            if entry.offset + entry.size > src.len() as u64 {
+                return Err(InjectionError::WadError(format!("Entry {:016x} points outside of file", entry.name)));
+            }
+            entries.push(entry);
+        }
+
+        Ok(Some(Self { major, minor, entries }))
+    }
+}
+
+// In-memory WAD archive, entries sorted by path hash
+#[derive(Debug, Clone, Default)]
+pub struct WadArchive {
+    pub entries: BTreeMap<u64, WadEntry>,
+    // Original paths for entries packed from loose files, used for reporting
+    pub names: HashMap<u64, String>,
+}
+
+impl WadArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn from_toc(toc: &Toc, backing: Backing, optimal: bool) -> Self {
+        let mut archive = Self::new();
+        for entry in &toc.entries {
+            let data = EntryData { backing: backing.clone(), offset: entry.offset as usize, size: entry.size as usize };
+            let checksum = if entry.checksum != 0 { entry.checksum } else { xxh3_64(data.bytes()) };
+            archive.entries.insert(entry.name, WadEntry {
+                entry_type: entry.entry_type,
+                subchunk_count: entry.subchunk_count,
+                subchunk_index: entry.subchunk_index,
+                size_decompressed: entry.size_decompressed,
+                checksum,
+                data,
+                optimal,
+            });
+        }
+        archive
+    }
+
+    fn map_file(path: &Path) -> Result<Option<Arc<Mmap>>, InjectionError> {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(None);
+        }
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+        Ok(Some(Arc::new(mmap)))
+    }
+
+    // Read a WAD shipped by the game. Only the latest version is accepted, like mod-tools does.
+    pub fn read_from_game_file(path: &Path) -> Result<Self, InjectionError> {
+        let mmap = match Self::map_file(path)? {
+            Some(mmap) => mmap,
+            None => return Ok(Self::new()),
+        };
+        let toc = match Toc::read(&mmap[..])? {
+            Some(toc) => toc,
+            None => return Ok(Self::new()),
+        };
+        if !toc.is_latest() {
+            return Err(InjectionError::WadError(format!(
+                "Unknown WAD version {}.{} in {}", toc.major, toc.minor, path.display()
+            )));
+        }
+        Ok(Self::from_toc(&toc, Backing::Mapped(mmap), true))
+    }
+
+    // Read any supported WAD version, e.g. one shipped inside a mod
+    pub fn read_from_file(path: &Path) -> Result<Self, InjectionError> {
+        let mmap = match Self::map_file(path)? {
+            Some(mmap) => mmap,
+            None => return Ok(Self::new()),
+        };
+        let toc = match Toc::read(&mmap[..])? {
+            Some(toc) => toc,
+            None => return Ok(Self::new()),
+        };
+        Ok(Self::from_toc(&toc, Backing::Mapped(mmap), false))
+    }
+
+    // Read a WAD from an in-memory buffer (e.g. a file inside a fantome archive)
+    pub fn read_from_bytes(bytes: Vec<u8>) -> Result<Self, InjectionError> {
+        let toc = match Toc::read(&bytes)? {
+            Some(toc) => toc,
+            None => return Ok(Self::new()),
+        };
+        Ok(Self::from_toc(&toc, Backing::Owned(Arc::new(bytes)), false))
+    }
+
+    // Pack a folder of loose files (e.g. WAD/Ahri.wad.client/) into an archive
+    pub fn pack_from_directory(dir: &Path) -> Result<Self, InjectionError> {
+        let mut archive = Self::new();
+        for entry in WalkDir::new(dir) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let rel_path = match generic_relative_path(entry.path(), dir) {
+                Some(rel) => rel,
+                None => continue,
+            };
+            let bytes = fs::read(entry.path())?;
+            let name = hash_path(&rel_path);
+            archive.entries.insert(name, WadEntry::from_raw(bytes));
+            archive.names.insert(name, rel_path.to_lowercase());
+        }
+        Ok(archive)
+    }
+
+    // Copy every entry from other, replacing existing ones
+    pub fn merge_in(&mut self, other: &WadArchive) {
+        for (name, entry) in &other.entries {
+            self.entries.insert(*name, entry.clone());
+        }
+    }
+
+    // Entries of upper whose path hash also exists in this archive
+    pub fn overlapping(&self, upper: &WadArchive) -> WadArchive {
+        let mut result = WadArchive::new();
+        for (name, entry) in &upper.entries {
+            if self.entries.contains_key(name) {
+                result.entries.insert(*name, entry.clone());
+            }
+        }
+        result
+    }
+
+    // Readable path for an entry, falling back to its hash
+    pub fn entry_name(&self, name: u64) -> String {
+        self.names.get(&name).cloned().unwrap_or_else(|| format!("{:016x}", name))
+    }
+
+    pub fn overlap_count(&self, other: &WadArchive) -> usize {
+        other.entries.keys().filter(|name| self.entries.contains_key(name)).count()
+    }
+
+    // WAD v3.4 signature: xxh3-128 over the version and every (name, checksum) pair
+    fn signature(entries: &[(u64, WadEntry)]) -> [u8; 16] {
+        let mut hasher = Xxh3::new();
+        hasher.update(&[WAD_MAGIC[0], WAD_MAGIC[1], LATEST_MAJOR, LATEST_MINOR]);
+        for (name, entry) in entries {
+            hasher.update(&name.to_le_bytes());
+            hasher.update(&entry.checksum.to_le_bytes());
+        }
+        hasher.digest128().to_le_bytes()
+    }
+
+    // Write the archive as a v3.4 WAD. Returns false when an identical file was already there.
+    pub fn write_to_file(&self, path: &Path) -> Result<bool, InjectionError> {
+        let mut optimal = Vec::with_capacity(self.entries.len());
+        for (name, entry) in &self.entries {
+            optimal.push((*name, entry.to_optimal()?));
+        }
+
+        let mut header = vec![0u8; HEADER_V3_SIZE];
+        header[0..2].copy_from_slice(&WAD_MAGIC);
+        header[2] = LATEST_MAJOR;
+        header[3] = LATEST_MINOR;
+        header[4..20].copy_from_slice(&Self::signature(&optimal));
+        header[268..272].copy_from_slice(&(optimal.len() as u32).to_le_bytes());
+
+        // Skip rewriting WADs that did not change since the last overlay build
+        if let Ok(mut existing) = File::open(path) {
+            let mut old_header = vec![0u8; HEADER_V3_SIZE];
+            if existing.read_exact(&mut old_header).is_ok() && old_header == header {
+                return Ok(false);
+            }
+        }
+
+        // Lay out data, deduplicating identical blobs by checksum
+        let mut data_cur = (HEADER_V3_SIZE + ENTRY_V3_SIZE * optimal.len()) as u64;
+        let mut loc_by_checksum: HashMap<u64, u64> = HashMap::new();
+        let mut toc = Vec::with_capacity(ENTRY_V3_SIZE * optimal.len());
+        let mut blobs: Vec<&EntryData> = Vec::new();
+        for (name, entry) in &optimal {
+            let offset = match loc_by_checksum.get(&entry.checksum) {
+                Some(offset) => *offset,
+                None => {
+                    let offset = data_cur;
+                    loc_by_checksum.insert(entry.checksum, offset);
+                    blobs.push(&entry.data);
+                    data_cur += entry.data.len() as u64;
+                    offset
+                }
+            };
+            if offset > MAX_WAD_OFFSET || entry.size_decompressed > MAX_WAD_OFFSET {
+                return Err(InjectionError::WadError(format!("WAD too large: {}", path.display())));
+            }
+            toc.extend_from_slice(&name.to_le_bytes());
+            toc.extend_from_slice(&(offset as u32).to_le_bytes());
+            toc.extend_from_slice(&(entry.data.len() as u32).to_le_bytes());
+            toc.extend_from_slice(&(entry.size_decompressed as u32).to_le_bytes());
+            toc.push(entry.entry_type.as_u8() | (entry.subchunk_count << 4));
+            // Mixed-endian uint24: hi, lo, mi
+            toc.push((entry.subchunk_index >> 16) as u8);
+            toc.push(entry.subchunk_index as u8);
+            toc.push((entry.subchunk_index >> 8) as u8);
+            toc.extend_from_slice(&entry.checksum.to_le_bytes());
+        }
+        if data_cur > MAX_WAD_OFFSET {
+            return Err(InjectionError::WadError(format!("WAD too large: {}", path.display())));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&header)?;
+        writer.write_all(&toc)?;
+        for blob in blobs {
+            writer.write_all(blob.bytes())?;
+        }
+        writer.flush()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    fn gzip_entry(bytes: &[u8]) -> WadEntry {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        let compressed = encoder.finish().unwrap();
+        WadEntry {
+            entry_type: EntryType::Gzip,
+            subchunk_count: 0,
+            subchunk_index: 0,
+            size_decompressed: bytes.len() as u64,
+            checksum: xxh3_64(&compressed),
+            data: EntryData::from_vec(compressed),
+            optimal: false,
+        }
+    }
+
+    fn zstd_entry(bytes: &[u8]) -> WadEntry {
+        let compressed = zstd::stream::encode_all(bytes, 3).unwrap();
+        let mut entry = WadEntry::from_zstd(compressed, bytes.len() as u64);
+        entry.optimal = false;
+        entry
+    }
+
+    #[test]
+    fn write_read_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("DATA").join("FINAL").join("Champions").join("Ahri.wad.client");
+
+        let texture = b"texture data ".repeat(64);
+        let skin = b"skin0.bin contents".to_vec();
+        let bank = b"BKHD audio bank, stays uncompressed".to_vec();
+        let mut archive = WadArchive::new();
+        archive.entries.insert(hash_path("assets/ahri.tex"), zstd_entry(&texture));
+        archive.entries.insert(hash_path("data/skin0.bin"), gzip_entry(&skin));
+        archive.entries.insert(hash_path("assets/ahri.bnk"), WadEntry::from_raw(bank.clone()));
+        assert!(archive.write_to_file(&path).unwrap());
+
+        let read = WadArchive::read_from_game_file(&path).unwrap();
+        assert_eq!(read.entries.len(), 3);
+        let entry = |name: &str| &read.entries[&hash_path(name)];
+        assert_eq!(entry("assets/ahri.tex").entry_type, EntryType::Zstd);
+        assert_eq!(entry("assets/ahri.tex").decompressed().unwrap(), texture);
+        // Gzip isn't what the game expects, it is rewritten as zstd
+        assert_eq!(entry("data/skin0.bin").entry_type, EntryType::Zstd);
+        assert_eq!(entry("data/skin0.bin").decompressed().unwrap(), skin);
+        assert_eq!(entry("assets/ahri.bnk").entry_type, EntryType::Raw);
+        assert_eq!(entry("assets/ahri.bnk").decompressed().unwrap(), bank);
+        for entry in read.entries.values() {
+            assert_eq!(entry.checksum, xxh3_64(entry.data.bytes()));
+            assert_eq!(entry.size_decompressed, entry.decompressed().unwrap().len() as u64);
+        }
+
+        // Same entries, same signature: the file is left alone
+        assert!(!read.write_to_file(&path).unwrap());
+        let from_bytes = WadArchive::read_from_bytes(fs::read(&path).unwrap()).unwrap();
+        let checksums = |archive: &WadArchive| archive.entries.iter().map(|(n, e)| (*n, e.checksum)).collect::<Vec<_>>();
+        assert_eq!(checksums(&from_bytes), checksums(&read));
+    }
+
+    #[test]
+    fn identical_entries_share_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Shared.wad.client");
+        let mut archive = WadArchive::new();
+        archive.entries.insert(hash_path("a.bin"), WadEntry::from_raw(b"same bytes".to_vec()));
+        archive.entries.insert(hash_path("b.bin"), WadEntry::from_raw(b"same bytes".to_vec()));
+        archive.write_to_file(&path).unwrap();
+
+        let toc = Toc::read(&fs::read(&path).unwrap()).unwrap().unwrap();
+        assert!(toc.is_latest());
+        assert_eq!(toc.entries.len(), 2);
+        assert_eq!(toc.entries[0].offset, toc.entries[1].offset);
+        assert_eq!(toc.entries[0].checksum, toc.entries[1].checksum);
+    }
+
+    #[test]
+    fn rejects_corrupt_toc() {
+        assert!(Toc::read(&[0, 0, 0, 0]).unwrap().is_none());
+        assert!(Toc::read(b"XX\x03\x04").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Truncated.wad.client");
+        let mut archive = WadArchive::new();
+        archive.entries.insert(hash_path("a.bin"), WadEntry::from_raw(b"entry data".to_vec()));
+        archive.write_to_file(&path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert!(Toc::read(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
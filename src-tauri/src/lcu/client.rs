@@ -0,0 +1,155 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+use base64::Engine;
+use reqwest::blocking::{Client, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::lcu::error::LcuError;
+use crate::lcu::gameflow::{GameflowPhase, Snapshot};
+use crate::lcu::lockfile::Lockfile;
+use crate::lcu::models::*;
+use crate::lcu::process;
+
+// Client for one running League client, built from its lockfile.
+// The API is served over HTTPS on localhost with a self-signed certificate and
+// basic auth as "riot:<password>".
+
+const HOST: &str = "127.0.0.1";
+
+// Shared HTTP client so connections are reused across watcher cycles and commands
+fn http_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .danger_accept_invalid_certs(true)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    }).clone()
+}
+
+#[derive(Debug, Clone)]
+pub struct LcuClient {
+    lockfile: Lockfile,
+    auth: String,
+    http: Client,
+}
+
+impl LcuClient {
+    pub fn new(lockfile: Lockfile) -> Self {
+        let auth = base64::engine::general_purpose::STANDARD.encode(format!("riot:{}", lockfile.password));
+        Self {
+            lockfile,
+            auth: format!("Basic {}", auth),
+            http: http_client(),
+        }
+    }
+
+    // Client for the League install at league_path, or failing that any running
+    // client (non-default installs, a stale saved path, a lockfile left by a crash)
+    pub fn connect(league_path: &str) -> Result<Self, LcuError> {
+        match Lockfile::find(league_path) {
+            Ok((_, lockfile)) if lockfile.is_listening() => Ok(Self::new(lockfile)),
+            Ok((path, lockfile)) => process::discover().map(Self::new).map_err(|_| {
+                LcuError::NotRunning(format!("{} is stale, nothing listens on port {}", path.display(), lockfile.port))
+            }),
+            Err(e) => process::discover().map(Self::new).map_err(|_| e),
+        }
+    }
+
+    pub fn lockfile(&self) -> &Lockfile {
+        &self.lockfile
+    }
+
+    // Authorization header value, shared with the event socket
+    pub fn auth(&self) -> &str {
+        &self.auth
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}://{}:{}{}", self.lockfile.protocol, HOST, self.lockfile.port, path)
+    }
+
+    fn check(path: &str, resp: Response) -> Result<Response, LcuError> {
+        if resp.status().is_success() {
+            Ok(resp)
+        } else {
+            Err(LcuError::ApiError { path: path.to_string(), status: resp.status().as_u16() })
+        }
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, LcuError> {
+        let resp = self.http.get(self.url(path))
+            .header("Authorization", &self.auth)
+            .send()?;
+        Self::check(path, resp)?
+            .json::<T>()
+            .map_err(|e| LcuError::DecodeError { path: path.to_string(), message: e.to_string() })
+    }
+
+    pub fn post<B: Serialize + ?Sized>(&self, path: &str, body: &B) -> Result<(), LcuError> {
+        let resp = self.http.post(self.url(path))
+            .header("Authorization", &self.auth)
+            .json(body)
+            .send()?;
+        Self::check(path, resp).map(|_| ())
+    }
+
+    // Gameflow
+
+    pub fn gameflow_phase(&self) -> Result<GameflowPhase, LcuError> {
+        self.get("/lol-gameflow/v1/gameflow-phase")
+    }
+
+    pub fn gameflow_session(&self) -> Result<GameflowSession, LcuError> {
+        self.get("/lol-gameflow/v1/session")
+    }
+
+    // Everything the gameflow tracker looks at. Endpoints that don't apply to the
+    // current phase answer 404 and stay empty.
+    pub fn snapshot(&self, phase: GameflowPhase) -> Snapshot {
+        Snapshot {
+            phase,
+            session: self.gameflow_session().ok(),
+            lobby: self.lobby().ok(),
+            champ_select: self.champ_select_session().ok(),
+        }
+    }
+
+    // Lobby and champ select
+
+    pub fn lobby(&self) -> Result<Lobby, LcuError> {
+        self.get("/lol-lobby/v2/lobby")
+    }
+
+    pub fn champ_select_session(&self) -> Result<ChampSelectSession, LcuError> {
+        self.get("/lol-champ-select/v1/session")
+    }
+
+    // Summoner and chat
+
+    pub fn current_summoner(&self) -> Result<Summoner, LcuError> {
+        self.get("/lol-summoner/v1/current-summoner")
+    }
+
+    pub fn friends(&self) -> Result<Vec<Friend>, LcuError> {
+        self.get("/lol-chat/v1/friends")
+    }
+
+    // Messages of a conversation; one that was never started has none
+    pub fn messages(&self, conversation_id: &str) -> Result<Vec<ChatMessage>, LcuError> {
+        match self.get(&format!("/lol-chat/v1/conversations/{}/messages", conversation_id)) {
+            Err(e) if e.is_not_found() => Ok(Vec::new()),
+            result => result,
+        }
+    }
+
+    pub fn send_message(&self, conversation_id: &str, body: &str) -> Result<(), LcuError> {
+        self.post(
+            &format!("/lol-chat/v1/conversations/{}/messages", conversation_id),
+            &NewChatMessage { body, message_type: "chat" },
+        )
+    }
+}
@@ -0,0 +1,54 @@
+use std::io;
+
+// Errors talking to the League client API
+#[derive(Debug)]
+pub enum LcuError {
+    NotRunning(String), // No lockfile, the client isn't started
+    InvalidLockfile(String),
+    IoError(io::Error),
+    RequestError(reqwest::Error), // Connection refused, timeout, TLS...
+    ApiError { path: String, status: u16 },
+    DecodeError { path: String, message: String },
+    SocketError(String), // Event WebSocket failed to connect or dropped
+}
+
+impl LcuError {
+    // The endpoint exists but has nothing right now (e.g. champ select outside of a game)
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::ApiError { status: 404, .. })
+    }
+}
+
+impl std::fmt::Display for LcuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotRunning(msg) => write!(f, "League client not running: {}", msg),
+            Self::InvalidLockfile(msg) => write!(f, "Invalid lockfile: {}", msg),
+            Self::IoError(err) => write!(f, "IO Error: {}", err),
+            Self::RequestError(err) => write!(f, "Failed to connect to LCU API: {}", err),
+            Self::ApiError { path, status } => write!(f, "LCU API returned error: {} for {}", status, path),
+            Self::DecodeError { path, message } => write!(f, "Failed to parse LCU response from {}: {}", path, message),
+            Self::SocketError(msg) => write!(f, "LCU event socket error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LcuError {}
+
+impl From<io::Error> for LcuError {
+    fn from(err: io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<reqwest::Error> for LcuError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::RequestError(err)
+    }
+}
+
+impl From<tungstenite::Error> for LcuError {
+    fn from(err: tungstenite::Error) -> Self {
+        Self::SocketError(err.to_string())
+    }
+}
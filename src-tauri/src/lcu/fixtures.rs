@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+use crate::lcu::gameflow::{Action, GameflowTracker, Snapshot, Step};
+
+pub use crate::lcu::gameflow::{next_action, GameflowPhase, LockStatus, QueueKind};
+
+// Every input of the transition table, for walking all of next_action
+pub const PHASES: [GameflowPhase; 15] = [
+    GameflowPhase::None,
+    GameflowPhase::Lobby,
+    GameflowPhase::Matchmaking,
+    GameflowPhase::CheckedIntoTournament,
+    GameflowPhase::ReadyCheck,
+    GameflowPhase::ChampSelect,
+    GameflowPhase::GameStart,
+    GameflowPhase::FailedToLaunch,
+    GameflowPhase::InProgress,
+    GameflowPhase::Reconnect,
+    GameflowPhase::WaitingForStats,
+    GameflowPhase::PreEndOfGame,
+    GameflowPhase::EndOfGame,
+    GameflowPhase::TerminatedInError,
+    GameflowPhase::Unknown,
+];
+pub const QUEUE_KINDS: [QueueKind; 2] = [QueueKind::Standard, QueueKind::SwiftPlay];
+pub const LOCK_STATUSES: [LockStatus; 4] = [LockStatus::Unlocked, LockStatus::Locked, LockStatus::Injected, LockStatus::Changed];
+
+// Client state captured through whole games, one snapshot per watcher cycle along
+// with the actions the tracker has to produce for it and the champions it injects
+pub const RECORDINGS: [&str; 4] = [
+    include_str!("fixtures/ranked_draft.json"),
+    include_str!("fixtures/aram.json"),
+    include_str!("fixtures/swift_play.json"),
+    include_str!("fixtures/brawl.json"),
+];
+
+// Friends and conversations served by the mock client's chat endpoints
+pub const CHAT: &str = include_str!("fixtures/chat.json");
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordedStep {
+    #[serde(flatten)]
+    pub snapshot: Snapshot,
+    pub expect: Vec<Action>,
+    #[serde(default)]
+    pub inject: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recording {
+    pub name: String,
+    pub puuid: Option<String>,
+    pub steps: Vec<RecordedStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub step: usize,
+    pub phase: GameflowPhase,
+    pub expected: Vec<Action>,
+    pub actual: Vec<Action>,
+    pub expected_inject: Vec<i64>,
+    pub inject: Vec<i64>,
+}
+
+impl RecordedStep {
+    // What the tracker did differently from the recording, if anything
+    pub fn check(&self, index: usize, step: Step) -> Option<Mismatch> {
+        let injects = step.actions.iter().any(|a| matches!(a, Action::Inject | Action::Reinject));
+        let inject = if injects { step.champion_ids } else { Vec::new() };
+        if step.actions == self.expect && inject == self.inject {
+            return None;
+        }
+        Some(Mismatch {
+            step: index,
+            phase: self.snapshot.phase,
+            expected: self.expect.clone(),
+            actual: step.actions,
+            expected_inject: self.inject.clone(),
+            inject,
+        })
+    }
+}
+
+impl Recording {
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn all() -> Result<Vec<Self>, serde_json::Error> {
+        RECORDINGS.iter().map(|json| Self::parse(json)).collect()
+    }
+
+    // Feed the steps through a fresh tracker; empty when every step did what was recorded
+    pub fn replay(&self) -> Vec<Mismatch> {
+        let mut tracker = GameflowTracker::default();
+        self.steps.iter().enumerate()
+            .filter_map(|(index, step)| step.check(index, tracker.step(&step.snapshot, self.puuid.as_deref())))
+            .collect()
+    }
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+
+use crate::lcu::models::*;
+
+// When to inject: the watcher feeds one snapshot of the client per cycle and the
+// tracker turns the phase change, the queue type and the pick's lock status into
+// an action. next_action is the whole transition table; everything it needs is
+// passed in so recorded sessions can be replayed through it.
+
+// /lol-gameflow/v1/gameflow-phase
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameflowPhase {
+    #[default]
+    None,
+    Lobby,
+    Matchmaking,
+    CheckedIntoTournament,
+    ReadyCheck,
+    ChampSelect,
+    GameStart,
+    FailedToLaunch,
+    InProgress,
+    Reconnect,
+    WaitingForStats,
+    PreEndOfGame,
+    EndOfGame,
+    TerminatedInError,
+    #[serde(other)]
+    Unknown, // Added by a client update
+}
+
+impl GameflowPhase {
+    pub fn parse(phase: &str) -> Self {
+        serde_json::from_value(serde_json::Value::String(phase.to_string())).unwrap_or(Self::Unknown)
+    }
+
+    // The game process is running
+    pub fn in_game(self) -> bool {
+        matches!(self, Self::GameStart | Self::InProgress | Self::Reconnect)
+    }
+}
+
+impl std::fmt::Display for GameflowPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueKind {
+    Standard,  // Champions are picked in champ select
+    SwiftPlay, // Champions are picked in the lobby before queueing
+}
+
+impl QueueKind {
+    // Phase in which the picks for the next game are made
+    pub fn pick_phase(self) -> GameflowPhase {
+        match self {
+            Self::Standard => GameflowPhase::ChampSelect,
+            Self::SwiftPlay => GameflowPhase::Lobby,
+        }
+    }
+
+    // Phases in which a locked pick is final enough to inject
+    pub fn injects_in(self, phase: GameflowPhase) -> bool {
+        use GameflowPhase::*;
+        match self {
+            Self::Standard => matches!(phase, ChampSelect | Matchmaking | GameStart | InProgress),
+            Self::SwiftPlay => matches!(phase, Matchmaking | GameStart | InProgress),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockStatus {
+    Unlocked, // No champion, or only hovered
+    Locked,   // Locked in and not injected yet
+    Injected, // Locked in and already injected
+    Changed,  // Locked in, but not the champions that were injected (trade, bench swap)
+}
+
+impl LockStatus {
+    pub fn new(locked: bool, champion_ids: &[i64], injected: &[i64]) -> Self {
+        if !locked || champion_ids.is_empty() {
+            Self::Unlocked
+        } else if injected.is_empty() {
+            Self::Locked
+        } else if champion_ids == injected {
+            Self::Injected
+        } else {
+            Self::Changed
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Prepare,  // Picks for a new game begin, forget the last injection
+    Inject,
+    Reinject, // Replace the injected skins with the new champions'
+    Cleanup,  // The game is over, stop the overlay
+    Nothing,
+}
+
+pub fn next_action(previous: GameflowPhase, current: GameflowPhase, queue: QueueKind, lock: LockStatus) -> Action {
+    use GameflowPhase::*;
+
+    if previous != current {
+        let game_over = previous.in_game() && !current.in_game();
+        if game_over || matches!(current, FailedToLaunch | TerminatedInError) {
+            return Action::Cleanup;
+        }
+        if current == queue.pick_phase() {
+            return Action::Prepare;
+        }
+    }
+    if !queue.injects_in(current) {
+        return Action::Nothing;
+    }
+    match lock {
+        LockStatus::Unlocked | LockStatus::Injected => Action::Nothing,
+        LockStatus::Locked => Action::Inject,
+        LockStatus::Changed => Action::Reinject,
+    }
+}
+
+// What the watcher read from the client in one cycle; endpoints with nothing to
+// say in the current phase are None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Snapshot {
+    pub phase: GameflowPhase,
+    pub session: Option<GameflowSession>,
+    pub lobby: Option<Lobby>,
+    pub champ_select: Option<ChampSelectSession>,
+}
+
+impl Snapshot {
+    pub fn queue(&self) -> QueueKind {
+        match &self.session {
+            Some(session) if session.is_swift_play() => QueueKind::SwiftPlay,
+            _ => QueueKind::Standard,
+        }
+    }
+
+    fn lobby_champions(&self) -> Vec<i64> {
+        self.lobby.as_ref().map(|lobby| lobby.locked_champions()).unwrap_or_default()
+    }
+
+    // The local player's champions: lobby slots in Swift Play, otherwise champ
+    // select, the game session and the lobby, first one that has any
+    pub fn champions(&self, puuid: Option<&str>) -> Vec<i64> {
+        use GameflowPhase::*;
+
+        if self.queue() == QueueKind::SwiftPlay {
+            if matches!(self.phase, Matchmaking | GameStart | InProgress) {
+                return self.lobby_champions();
+            }
+            return Vec::new();
+        }
+
+        let mut champion_ids = Vec::new();
+        if self.phase == Matchmaking {
+            champion_ids = self.lobby_champions();
+        }
+        if champion_ids.is_empty() {
+            champion_ids = self.champ_select.as_ref().map(|cs| cs.local_champions()).unwrap_or_default();
+        }
+        if champion_ids.is_empty() {
+            champion_ids = self.session.as_ref().map(|s| s.local_champions(puuid)).unwrap_or_default();
+        }
+        if champion_ids.is_empty() {
+            champion_ids = self.lobby_champions();
+        }
+        champion_ids
+    }
+
+    // In champ select a hovered champion isn't a pick yet; everywhere else the
+    // champions found above are final
+    pub fn is_locked(&self) -> bool {
+        if self.queue() == QueueKind::Standard && self.phase == GameflowPhase::ChampSelect {
+            return self.champ_select.as_ref().is_some_and(|cs| cs.has_locked_pick());
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub actions: Vec<Action>,
+    pub champion_ids: Vec<i64>,
+}
+
+// Phase and injected champions carried from one cycle to the next
+#[derive(Debug, Clone, Default)]
+pub struct GameflowTracker {
+    phase: GameflowPhase,
+    injected: Vec<i64>,
+}
+
+impl GameflowTracker {
+    pub fn phase(&self) -> GameflowPhase {
+        self.phase
+    }
+
+    // Actions for this snapshot, in order. Prepare is followed by whatever the
+    // same snapshot calls for once the last injection is forgotten.
+    pub fn step(&mut self, snapshot: &Snapshot, puuid: Option<&str>) -> Step {
+        let queue = snapshot.queue();
+        let champion_ids = snapshot.champions(puuid);
+        let locked = snapshot.is_locked();
+        let previous = std::mem::replace(&mut self.phase, snapshot.phase);
+
+        let mut actions = Vec::new();
+        let mut action = next_action(previous, snapshot.phase, queue, LockStatus::new(locked, &champion_ids, &self.injected));
+        if action == Action::Prepare {
+            self.injected.clear();
+            actions.push(action);
+            action = next_action(snapshot.phase, snapshot.phase, queue, LockStatus::new(locked, &champion_ids, &self.injected));
+        }
+        match action {
+            Action::Inject | Action::Reinject => self.injected = champion_ids.clone(),
+            Action::Cleanup => self.injected.clear(),
+            Action::Prepare | Action::Nothing => {}
+        }
+        if action != Action::Nothing || actions.is_empty() {
+            actions.push(action);
+        }
+        Step { actions, champion_ids }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lcu::fixtures::{Recording, LOCK_STATUSES, PHASES, QUEUE_KINDS};
+    use GameflowPhase::*;
+
+    const ANY: &[GameflowPhase] = &PHASES;
+    const ANY_LOCK: &[LockStatus] = &LOCK_STATUSES;
+    const IN_GAME: &[GameflowPhase] = &[GameStart, InProgress, Reconnect];
+    const OUT_OF_GAME: &[GameflowPhase] = &[
+        None, Lobby, Matchmaking, CheckedIntoTournament, ReadyCheck, ChampSelect,
+        FailedToLaunch, WaitingForStats, PreEndOfGame, EndOfGame, TerminatedInError, Unknown,
+    ];
+
+    // Every transition that prepares or injects: (previous phases, current phase,
+    // queue, lock statuses) -> action
+    type Phases = &'static [GameflowPhase];
+    type Row = (Phases, GameflowPhase, QueueKind, &'static [LockStatus], Action);
+    const TRANSITIONS: &[Row] = &[
+        // Standard queues pick in champ select, unless a game was just left
+        (
+            &[None, Lobby, Matchmaking, CheckedIntoTournament, ReadyCheck,
+              FailedToLaunch, WaitingForStats, PreEndOfGame, EndOfGame, TerminatedInError, Unknown],
+            ChampSelect, QueueKind::Standard, ANY_LOCK, Action::Prepare,
+        ),
+        (&[ChampSelect], ChampSelect, QueueKind::Standard, &[LockStatus::Locked], Action::Inject),
+        (&[ChampSelect], ChampSelect, QueueKind::Standard, &[LockStatus::Changed], Action::Reinject),
+        (OUT_OF_GAME, Matchmaking, QueueKind::Standard, &[LockStatus::Locked], Action::Inject),
+        (OUT_OF_GAME, Matchmaking, QueueKind::Standard, &[LockStatus::Changed], Action::Reinject),
+        (ANY, GameStart, QueueKind::Standard, &[LockStatus::Locked], Action::Inject),
+        (ANY, GameStart, QueueKind::Standard, &[LockStatus::Changed], Action::Reinject),
+        (ANY, InProgress, QueueKind::Standard, &[LockStatus::Locked], Action::Inject),
+        (ANY, InProgress, QueueKind::Standard, &[LockStatus::Changed], Action::Reinject),
+        // Swift Play picks in the lobby and injects once queueing starts
+        (
+            &[None, Matchmaking, CheckedIntoTournament, ReadyCheck, ChampSelect,
+              FailedToLaunch, WaitingForStats, PreEndOfGame, EndOfGame, TerminatedInError, Unknown],
+            Lobby, QueueKind::SwiftPlay, ANY_LOCK, Action::Prepare,
+        ),
+        (OUT_OF_GAME, Matchmaking, QueueKind::SwiftPlay, &[LockStatus::Locked], Action::Inject),
+        (OUT_OF_GAME, Matchmaking, QueueKind::SwiftPlay, &[LockStatus::Changed], Action::Reinject),
+        (ANY, GameStart, QueueKind::SwiftPlay, &[LockStatus::Locked], Action::Inject),
+        (ANY, GameStart, QueueKind::SwiftPlay, &[LockStatus::Changed], Action::Reinject),
+        (ANY, InProgress, QueueKind::SwiftPlay, &[LockStatus::Locked], Action::Inject),
+        (ANY, InProgress, QueueKind::SwiftPlay, &[LockStatus::Changed], Action::Reinject),
+    ];
+
+    #[test]
+    fn every_transition() {
+        let mut listed = 0;
+        for previous in PHASES {
+            for current in PHASES {
+                for queue in QUEUE_KINDS {
+                    for lock in LOCK_STATUSES {
+                        let rows: Vec<&Row> = TRANSITIONS.iter()
+                            .filter(|(from, to, row_queue, locks, _)| {
+                                from.contains(&previous) && *to == current && *row_queue == queue && locks.contains(&lock)
+                            })
+                            .collect();
+                        let transition = format!("{} -> {} in {:?} with {:?}", previous, current, queue, lock);
+                        assert!(rows.len() <= 1, "{} is listed twice", transition);
+
+                        // Anything not listed only ever cleans up after a game or does nothing
+                        let game_over = IN_GAME.contains(&previous) && !IN_GAME.contains(&current);
+                        let failed = current == FailedToLaunch || current == TerminatedInError;
+                        let expected = match rows.first() {
+                            Some(row) => row.4,
+                            _ if previous != current && (game_over || failed) => Action::Cleanup,
+                            _ => Action::Nothing,
+                        };
+                        assert_eq!(next_action(previous, current, queue, lock), expected, "{}", transition);
+                        listed += rows.len();
+                    }
+                }
+            }
+        }
+        let rows: usize = TRANSITIONS.iter().map(|row| row.0.len() * row.3.len()).sum();
+        assert_eq!(listed, rows);
+    }
+
+    #[test]
+    fn recorded_sessions() {
+        let recordings = Recording::all().unwrap();
+        assert_eq!(recordings.len(), 4);
+        for recording in recordings {
+            let mismatches = recording.replay();
+            assert!(mismatches.is_empty(), "{}: {:#?}", recording.name, mismatches);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::lcu::error::LcuError;
+
+// The client writes "<name>:<pid>:<port>:<password>:<protocol>" to a lockfile in
+// the install folder while it runs, e.g. "LeagueClient:12345:54321:hunter2:https"
+pub const LOCKFILE_NAMES: [&str; 3] = ["lockfile", "LeagueClientUx.lockfile", "LeagueClient.lockfile"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    pub name: String,
+    pub pid: u32,
+    pub port: u16,
+    pub password: String,
+    pub protocol: String,
+}
+
+impl Lockfile {
+    pub fn parse(content: &str) -> Result<Self, LcuError> {
+        let parts: Vec<&str> = content.trim().split(':').collect();
+        if parts.len() != 5 {
+            return Err(LcuError::InvalidLockfile(format!("expected 5 fields, found {}", parts.len())));
+        }
+        let pid = parts[1].parse()
+            .map_err(|_| LcuError::InvalidLockfile(format!("bad pid: {}", parts[1])))?;
+        let port = parts[2].parse()
+            .map_err(|_| LcuError::InvalidLockfile(format!("bad port: {}", parts[2])))?;
+        if parts[3].is_empty() {
+            return Err(LcuError::InvalidLockfile("empty password".into()));
+        }
+        Ok(Self {
+            name: parts[0].to_string(),
+            pid,
+            port,
+            password: parts[3].to_string(),
+            protocol: parts[4].to_string(),
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LcuError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    // Whether something accepts connections on the lockfile's port. A client that
+    // crashed leaves its lockfile behind, pointing at a closed port.
+    pub fn is_listening(&self) -> bool {
+        let addr = SocketAddr::from(([127, 0, 0, 1], self.port));
+        TcpStream::connect_timeout(&addr, Duration::from_millis(500)).is_ok()
+    }
+
+    // First lockfile in the League folder that parses
+    pub fn find(league_path: &str) -> Result<(PathBuf, Self), LcuError> {
+        let dir = Path::new(league_path);
+        let mut last_error = None;
+        for name in LOCKFILE_NAMES {
+            let path = dir.join(name);
+            if !path.exists() {
+                continue;
+            }
+            match Self::read(&path) {
+                Ok(lockfile) => return Ok((path, lockfile)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            LcuError::NotRunning("Lockfile not found. Is League of Legends running?".into())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn lockfile_of_a_closed_client_is_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        fs::write(dir.path().join("lockfile"), format!("LeagueClient:4242:{}:hunter2:https", port)).unwrap();
+
+        let (path, lockfile) = Lockfile::find(&dir.path().to_string_lossy()).unwrap();
+        assert_eq!(path, dir.path().join("lockfile"));
+        assert_eq!((lockfile.pid, lockfile.port, lockfile.password.as_str()), (4242, port, "hunter2"));
+        assert!(lockfile.is_listening());
+
+        drop(listener);
+        assert!(!lockfile.is_listening());
+    }
+}
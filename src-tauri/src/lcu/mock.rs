@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use base64::Engine;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::lcu::client::LcuClient;
+use crate::lcu::error::LcuError;
+use crate::lcu::fixtures::{Mismatch, Recording};
+use crate::lcu::gameflow::GameflowTracker;
+use crate::lcu::lockfile::Lockfile;
+use crate::lcu::models::{ChatMessage, Friend};
+
+// Local stand-in for the League client: HTTPS on 127.0.0.1 with a self-signed
+// certificate and basic auth, like the real one. Gameflow, lobby and champ select
+// answer from the current step of a recording, chat from the chat fixture.
+// There is no event socket, so a watcher pointed at it falls back to polling.
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ChatFixture {
+    friends: Vec<Friend>,
+    conversations: HashMap<String, Vec<ChatMessage>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    recording: Option<Recording>,
+    step: usize,
+    chat: ChatFixture,
+    requests: Vec<String>,
+}
+
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn json(body: &impl serde::Serialize) -> Self {
+        Self { status: 200, body: serde_json::to_string(body).unwrap_or_default() }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        let body = serde_json::json!({ "errorCode": "RPC_ERROR", "httpStatus": status, "message": message });
+        Self { status, body: body.to_string() }
+    }
+}
+
+pub struct MockLcu {
+    port: u16,
+    password: String,
+    state: Arc<Mutex<MockState>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MockLcu {
+    pub fn start() -> Result<Self, LcuError> {
+        let tls = Arc::new(tls_config()?);
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+
+        // Changes with every server, so a stale lockfile can't authenticate
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        let password = format!("mock-{:08x}{:04x}", nanos, port);
+        let auth = format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!("riot:{}", password)));
+
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let server_state = state.clone();
+        let server_stopped = stopped.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if server_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                let (tls, state, auth) = (tls.clone(), server_state.clone(), auth.clone());
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, tls, &state, &auth) {
+                        println!("[Mock LCU] Connection error: {}", e);
+                    }
+                });
+            }
+        });
+
+        println!("[Mock LCU] Listening on 127.0.0.1:{}", port);
+        Ok(Self { port, password, state, stopped })
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn lockfile(&self) -> Lockfile {
+        Lockfile {
+            name: "LeagueClient".to_string(),
+            pid: std::process::id(),
+            port: self.port,
+            password: self.password.clone(),
+            protocol: "https".to_string(),
+        }
+    }
+
+    // Write the lockfile into a League folder, so LcuClient::connect finds this server
+    pub fn write_lockfile(&self, league_path: &Path) -> Result<PathBuf, LcuError> {
+        let lockfile = self.lockfile();
+        let path = league_path.join("lockfile");
+        fs::create_dir_all(league_path)?;
+        fs::write(&path, format!(
+            "{}:{}:{}:{}:{}",
+            lockfile.name, lockfile.pid, lockfile.port, lockfile.password, lockfile.protocol
+        ))?;
+        Ok(path)
+    }
+
+    pub fn client(&self) -> LcuClient {
+        LcuClient::new(self.lockfile())
+    }
+
+    // Serve the first step of a recording
+    pub fn play(&self, recording: Recording) {
+        let mut state = self.state();
+        state.recording = Some(recording);
+        state.step = 0;
+    }
+
+    // Move on to the next step; false once the recording is over
+    pub fn advance(&self) -> bool {
+        let mut state = self.state();
+        let steps = state.recording.as_ref().map_or(0, |r| r.steps.len());
+        if state.step + 1 >= steps {
+            return false;
+        }
+        state.step += 1;
+        true
+    }
+
+    pub fn step(&self) -> usize {
+        self.state().step
+    }
+
+    pub fn load_chat(&self, json: &str) -> Result<(), serde_json::Error> {
+        self.state().chat = serde_json::from_str(json)?;
+        Ok(())
+    }
+
+    // Messages of a conversation, including the ones sent through the API
+    pub fn messages(&self, conversation_id: &str) -> Vec<ChatMessage> {
+        self.state().chat.conversations.get(conversation_id).cloned().unwrap_or_default()
+    }
+
+    // "GET /lol-gameflow/v1/gameflow-phase" and so on, in the order they came in
+    pub fn requests(&self) -> Vec<String> {
+        self.state().requests.clone()
+    }
+
+    // Play a recording and run the watcher's detection against it over HTTPS, one
+    // cycle per step. Empty when every step did what was recorded.
+    pub fn replay(&self, recording: Recording) -> Result<Vec<Mismatch>, LcuError> {
+        let steps = recording.steps.clone();
+        self.play(recording);
+
+        let client = self.client();
+        let puuid = client.current_summoner()?.puuid;
+        let mut tracker = GameflowTracker::default();
+        let mut mismatches = Vec::new();
+        for (index, recorded) in steps.iter().enumerate() {
+            let snapshot = client.snapshot(client.gameflow_phase()?);
+            let step = tracker.step(&snapshot, Some(puuid.as_str()).filter(|p| !p.is_empty()));
+            mismatches.extend(recorded.check(index, step));
+            self.advance();
+        }
+        Ok(mismatches)
+    }
+}
+
+impl Drop for MockLcu {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+    }
+}
+
+fn tls_config() -> Result<ServerConfig, LcuError> {
+    let tls_error = |e: String| LcuError::IoError(io::Error::other(e));
+
+    let certified = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string(), "localhost".to_string()])
+        .map_err(|e| tls_error(e.to_string()))?;
+    let cert = CertificateDer::from(certified.cert.der().to_vec());
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+    ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| tls_error(e.to_string()))?
+        .with_no_client_auth()
+        .with_single_cert(vec![cert], key)
+        .map_err(|e| tls_error(e.to_string()))
+}
+
+// One request per connection, answered with Connection: close
+fn serve(stream: TcpStream, tls: Arc<ServerConfig>, state: &Mutex<MockState>, auth: &str) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let conn = ServerConnection::new(tls).map_err(io::Error::other)?;
+    let mut stream = StreamOwned::new(conn, stream);
+
+    let mut reader = BufReader::new(&mut stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line)? == 0 {
+        return Ok(());
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorized = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorized = value.trim() == auth,
+                _ => {}
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let reply = if authorized {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.requests.push(format!("{} {}", method, path));
+        route(&mut state, &method, &path, &body)
+    } else {
+        Reply::error(401, "Unauthorized")
+    };
+
+    let reason = match reply.status {
+        200 => "OK",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Bad Request",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        reply.status, reason, reply.body.len(), reply.body
+    )?;
+    stream.flush()?;
+    stream.conn.send_close_notify();
+    let _ = stream.flush();
+    let _ = stream.sock.shutdown(Shutdown::Write);
+    Ok(())
+}
+
+fn route(state: &mut MockState, method: &str, path: &str, body: &[u8]) -> Reply {
+    let snapshot = state.recording.as_ref()
+        .and_then(|recording| recording.steps.get(state.step))
+        .map(|step| step.snapshot.clone())
+        .unwrap_or_default();
+    let local_puuid = state.recording.as_ref()
+        .and_then(|recording| recording.puuid.clone())
+        .unwrap_or_else(|| "local-puuid".to_string());
+    let not_found = || Reply::error(404, &format!("Invalid URI format: {}", path));
+
+    let conversation = path.strip_prefix("/lol-chat/v1/conversations/")
+        .and_then(|rest| rest.strip_suffix("/messages"));
+
+    match (method, path) {
+        ("GET", "/lol-gameflow/v1/gameflow-phase") => Reply::json(&snapshot.phase),
+        ("GET", "/lol-gameflow/v1/session") => snapshot.session.map_or_else(not_found, |s| Reply::json(&s)),
+        ("GET", "/lol-lobby/v2/lobby") => snapshot.lobby.map_or_else(not_found, |l| Reply::json(&l)),
+        ("GET", "/lol-champ-select/v1/session") => snapshot.champ_select.map_or_else(not_found, |cs| Reply::json(&cs)),
+        ("GET", "/lol-summoner/v1/current-summoner") => Reply::json(&serde_json::json!({
+            "puuid": local_puuid,
+            "summonerId": 1,
+            "gameName": "Mock",
+            "tagLine": "LCU",
+            "displayName": "Mock",
+        })),
+        ("GET", "/lol-chat/v1/friends") => Reply::json(&state.chat.friends),
+        ("GET", _) if conversation.is_some() => {
+            let id = conversation.unwrap_or_default();
+            state.chat.conversations.get(id).map_or_else(not_found, Reply::json)
+        }
+        ("POST", _) if conversation.is_some() => {
+            let id = conversation.unwrap_or_default().to_string();
+            let sent: Value = match serde_json::from_slice(body) {
+                Ok(sent) => sent,
+                Err(e) => return Reply::error(400, &e.to_string()),
+            };
+            let messages = state.chat.conversations.entry(id).or_default();
+            let message = ChatMessage {
+                id: format!("mock:{}", messages.len() + 1),
+                body: sent["body"].as_str().unwrap_or_default().to_string(),
+                from_id: local_puuid,
+                message_type: sent["type"].as_str().unwrap_or("chat").to_string(),
+                ..ChatMessage::default()
+            };
+            messages.push(message.clone());
+            Reply::json(&message)
+        }
+        _ => not_found(),
+    }
+}
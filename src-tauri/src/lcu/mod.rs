@@ -0,0 +1,22 @@
+// League client (LCU) API: lockfile and process discovery, a typed client, response models
+// and the WebSocket event stream
+mod client;
+mod error;
+// Recorded sessions need no extra dependencies, so the unit tests use them too
+#[cfg(any(test, feature = "test-support"))]
+pub mod fixtures;
+mod gameflow;
+mod lockfile;
+#[cfg(feature = "test-support")]
+pub mod mock;
+mod models;
+mod process;
+mod socket;
+mod watcher;
+
+pub use client::LcuClient;
+pub use error::LcuError;
+pub use gameflow::{Action, GameflowPhase, GameflowTracker, QueueKind, Step};
+pub use models::*;
+pub use socket::LcuSocket;
+pub use watcher::{GameflowWatcher, WatcherCycle};
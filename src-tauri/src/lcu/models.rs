@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+
+// Response models for the LCU endpoints we use
+// Only the fields we read are declared and all of them default, the client adds
+// fields every patch and sends partial objects outside of the matching game phase.
+
+// Swift Play (480, 1700) lets a player pick two champions, Brawl (1300, 2300) only one
+pub const SWIFT_PLAY_QUEUES: [i64; 2] = [480, 1700];
+pub const BRAWL_QUEUES: [i64; 2] = [1300, 2300];
+
+pub fn is_swift_play(queue_id: i64, game_mode: &str) -> bool {
+    let mode = game_mode.to_uppercase();
+    SWIFT_PLAY_QUEUES.contains(&queue_id) || mode.contains("SWIFT") || mode.contains("ARENA")
+}
+
+pub fn is_brawl(queue_id: i64, game_mode: &str) -> bool {
+    BRAWL_QUEUES.contains(&queue_id) || game_mode.to_uppercase().contains("BRAWL")
+}
+
+// Mode name for logs and mode checks: known queues first, then what the client reports
+fn mode_name(queue_id: i64, game_mode: &str) -> String {
+    if SWIFT_PLAY_QUEUES.contains(&queue_id) {
+        "SWIFT_PLAY".to_string()
+    } else if BRAWL_QUEUES.contains(&queue_id) {
+        "BRAWL".to_string()
+    } else if !game_mode.is_empty() {
+        game_mode.to_string()
+    } else {
+        "UNKNOWN".to_string()
+    }
+}
+
+fn push_champion(champion_ids: &mut Vec<i64>, champion_id: i64) {
+    if champion_id > 0 && !champion_ids.contains(&champion_id) {
+        champion_ids.push(champion_id);
+    }
+}
+
+// /lol-gameflow/v1/session
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameflowSession {
+    pub phase: String,
+    pub game_data: GameflowGameData,
+    pub map: GameflowMap,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameflowGameData {
+    pub game_id: u64,
+    pub queue: Queue,
+    pub player_champion_selections: Vec<PlayerChampionSelection>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Queue {
+    pub id: i64,
+    pub game_mode: String,
+    pub map_id: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameflowMap {
+    pub game_mode: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerChampionSelection {
+    pub champion_id: i64,
+    pub puuid: String,
+    pub summoner_internal_name: String,
+}
+
+impl GameflowSession {
+    pub fn queue_id(&self) -> i64 {
+        self.game_data.queue.id
+    }
+
+    pub fn game_mode(&self) -> String {
+        let mode = if self.game_data.queue.game_mode.is_empty() {
+            &self.map.game_mode
+        } else {
+            &self.game_data.queue.game_mode
+        };
+        mode_name(self.queue_id(), mode)
+    }
+
+    pub fn is_swift_play(&self) -> bool {
+        is_swift_play(self.queue_id(), &self.game_mode())
+    }
+
+    pub fn is_multi_champion(&self) -> bool {
+        let mode = self.game_mode();
+        !is_brawl(self.queue_id(), &mode) && is_swift_play(self.queue_id(), &mode)
+    }
+
+    // Champions of the local player. Selections are matched by puuid; without one
+    // only multi-champion modes can use them, everyone else's picks would come along.
+    pub fn local_champions(&self, local_puuid: Option<&str>) -> Vec<i64> {
+        let mut champion_ids = Vec::new();
+        for selection in &self.game_data.player_champion_selections {
+            let is_local = match local_puuid {
+                Some(puuid) => selection.puuid == puuid,
+                None => self.is_multi_champion(),
+            };
+            if is_local {
+                push_champion(&mut champion_ids, selection.champion_id);
+            }
+        }
+        champion_ids
+    }
+}
+
+// /lol-lobby/v2/lobby
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Lobby {
+    pub game_config: LobbyGameConfig,
+    pub local_member: LobbyMember,
+    pub members: Vec<LobbyMember>,
+    pub state: String, // Matchmaking state on Swift Play lobbies, empty otherwise
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LobbyGameConfig {
+    pub queue_id: i64,
+    pub game_mode: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LobbyMember {
+    pub puuid: String,
+    pub summoner_id: u64,
+    pub ready: bool,
+    pub champion_id: i64,
+    pub player_slots: Vec<PlayerSlot>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerSlot {
+    pub champion_id: i64,
+    pub skin_id: i64,
+    pub selection_status: String,
+}
+
+impl PlayerSlot {
+    pub fn is_locked(&self) -> bool {
+        self.selection_status == "SELECTED" || self.selection_status == "LOCKED"
+    }
+}
+
+impl Lobby {
+    pub fn queue_id(&self) -> i64 {
+        self.game_config.queue_id
+    }
+
+    pub fn game_mode(&self) -> String {
+        mode_name(self.queue_id(), &self.game_config.game_mode)
+    }
+
+    pub fn is_swift_play(&self) -> bool {
+        is_swift_play(self.queue_id(), &self.game_mode())
+    }
+
+    // Queue search or game start, picks can't change any more
+    pub fn in_matchmaking(&self) -> bool {
+        matches!(self.state.as_str(), "MATCHMAKING" | "GAMESTARTING" | "PREPARING" | "INPROGRESS")
+    }
+
+    // Ready, or past the point where slots can change
+    pub fn is_confirmed(&self) -> bool {
+        self.local_member.ready || matches!(self.state.as_str(), "MATCHMAKING" | "GAMESTARTING" | "INPROGRESS")
+    }
+
+    // Champions the local player has locked into their slots. Once matchmaking has
+    // started every filled slot counts; Brawl only uses the first slot.
+    pub fn locked_champions(&self) -> Vec<i64> {
+        let member = &self.local_member;
+        let mut champion_ids = Vec::new();
+
+        if self.in_matchmaking() {
+            for slot in &member.player_slots {
+                push_champion(&mut champion_ids, slot.champion_id);
+            }
+            if champion_ids.is_empty() {
+                push_champion(&mut champion_ids, member.champion_id);
+            }
+            if !champion_ids.is_empty() {
+                return champion_ids;
+            }
+        }
+
+        let confirmed = self.is_confirmed();
+        let swift_play = self.is_swift_play();
+        let slots = if is_brawl(self.queue_id(), &self.game_mode()) { 1 } else { member.player_slots.len() };
+        for slot in member.player_slots.iter().take(slots) {
+            // In Swift Play a filled slot is a pick, there is no separate lock
+            if slot.is_locked() || confirmed || swift_play {
+                push_champion(&mut champion_ids, slot.champion_id);
+            } else if slot.champion_id > 0 {
+                println!("[Lobby Champion] Skipping unlocked champion: {}", slot.champion_id);
+            }
+        }
+        if champion_ids.is_empty() && confirmed {
+            push_champion(&mut champion_ids, member.champion_id);
+        }
+        champion_ids
+    }
+}
+
+// /lol-champ-select/v1/session
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChampSelectSession {
+    pub local_player_cell_id: i64,
+    pub actions: Vec<Vec<ChampSelectAction>>,
+    pub my_team: Vec<ChampSelectPlayer>,
+    pub bench_enabled: bool,
+    pub bench_champions: Vec<BenchChampion>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChampSelectAction {
+    pub id: i64,
+    pub actor_cell_id: i64,
+    pub champion_id: i64,
+    pub completed: bool,
+    pub is_in_progress: bool,
+    #[serde(rename = "type")]
+    pub action_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChampSelectPlayer {
+    pub cell_id: i64,
+    pub champion_id: i64,
+    pub champion_pick_intent: i64,
+    pub selected_skin_id: i64,
+    pub puuid: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BenchChampion {
+    pub champion_id: i64,
+}
+
+impl ChampSelectSession {
+    fn local_actions(&self) -> impl Iterator<Item = &ChampSelectAction> {
+        self.actions.iter().flatten().filter(move |action| action.actor_cell_id == self.local_player_cell_id)
+    }
+
+    pub fn local_player(&self) -> Option<&ChampSelectPlayer> {
+        self.my_team.iter().find(|player| player.cell_id == self.local_player_cell_id)
+    }
+
+    // The local player has completed their pick action
+    pub fn has_locked_pick(&self) -> bool {
+        self.local_actions().any(|action| action.action_type == "pick" && action.completed)
+    }
+
+    // Champion of the local player's completed pick, None while they are still picking
+    pub fn picked_champion(&self) -> Option<i64> {
+        if self.local_actions().any(|action| action.action_type == "pick" && action.is_in_progress) {
+            return None;
+        }
+        self.local_actions()
+            .find(|action| action.action_type == "pick" && action.completed && action.champion_id > 0)
+            .map(|action| action.champion_id)
+    }
+
+    // The local player's champion: their team slot (also set in ARAM, where there
+    // are no pick actions) or, failing that, their completed pick
+    pub fn local_champions(&self) -> Vec<i64> {
+        match self.local_player().map(|player| player.champion_id).filter(|id| *id > 0) {
+            Some(champion_id) => vec![champion_id],
+            None => self.picked_champion().into_iter().collect(),
+        }
+    }
+}
+
+// /lol-summoner/v1/current-summoner
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Summoner {
+    pub puuid: String,
+    pub summoner_id: u64,
+    pub game_name: String,
+    pub tag_line: String,
+    pub display_name: String,
+}
+
+// /lol-chat/v1/friends and conversations
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Friend {
+    pub id: String,
+    pub name: String,
+    pub availability: String,
+    #[serde(rename = "gameTag")]
+    pub game_tag: Option<String>,
+    #[serde(rename = "note")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub id: String,
+    pub body: String,
+    pub from_id: String,
+    pub from_pid: String,
+    pub timestamp: String,
+    #[serde(rename = "type")]
+    pub message_type: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewChatMessage<'a> {
+    pub body: &'a str,
+    #[serde(rename = "type")]
+    pub message_type: &'a str,
+}
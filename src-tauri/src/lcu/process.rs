@@ -0,0 +1,273 @@
+#[cfg(not(target_os = "windows"))]
+use std::fs;
+#[cfg(not(target_os = "windows"))]
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::lcu::error::LcuError;
+use crate::lcu::lockfile::Lockfile;
+
+// The client UI process is started with the same values the lockfile holds, e.g.
+// LeagueClientUx.exe "--app-port=54321" "--remoting-auth-token=hunter2" "--install-directory=C:\..."
+// Reading them from the running process finds the client wherever it is installed.
+
+pub const CLIENT_PROCESS: &str = "LeagueClientUx";
+
+#[derive(Debug, Clone)]
+pub struct ClientProcess {
+    pub pid: u32,
+    pub args: Vec<String>,
+}
+
+impl ClientProcess {
+    // Value of a "--name=value" argument
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        let prefix = format!("--{}=", name);
+        self.args.iter()
+            .find_map(|arg| arg.strip_prefix(&prefix))
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn lockfile(&self) -> Result<Lockfile, LcuError> {
+        let port = self.arg("app-port")
+            .ok_or_else(|| LcuError::InvalidLockfile(format!("process {} has no --app-port", self.pid)))?;
+        let port = port.parse()
+            .map_err(|_| LcuError::InvalidLockfile(format!("bad port: {}", port)))?;
+        let password = self.arg("remoting-auth-token")
+            .ok_or_else(|| LcuError::InvalidLockfile(format!("process {} has no --remoting-auth-token", self.pid)))?;
+        Ok(Lockfile {
+            name: CLIENT_PROCESS.to_string(),
+            pid: self.pid,
+            port,
+            password: password.to_string(),
+            protocol: "https".to_string(),
+        })
+    }
+}
+
+// Running processes of the current platform
+pub trait ProcessSource {
+    // Processes whose executable is `name`, with or without ".exe"
+    fn processes(&self, name: &str) -> Vec<ClientProcess>;
+}
+
+// Connection details of the first running client whose command line carries them
+pub fn find_client(source: &impl ProcessSource) -> Result<Lockfile, LcuError> {
+    let mut last_error = None;
+    for process in source.processes(CLIENT_PROCESS) {
+        match process.lockfile() {
+            Ok(lockfile) => return Ok(lockfile),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        LcuError::NotRunning(format!("no {} process found", CLIENT_PROCESS))
+    }))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_executable(path: &str, name: &str) -> bool {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let stem = match file_name.len().checked_sub(4) {
+        Some(at) if file_name.is_char_boundary(at) && file_name[at..].eq_ignore_ascii_case(".exe") => &file_name[..at],
+        _ => file_name,
+    };
+    stem.eq_ignore_ascii_case(name)
+}
+
+// find_client on the system's processes. The watcher reconnects every few seconds
+// and a lookup starts powershell on Windows, so results are reused for a while.
+pub fn discover() -> Result<Lockfile, LcuError> {
+    const REFRESH: Duration = Duration::from_secs(10);
+    static LAST: Mutex<Option<(Instant, Option<Lockfile>)>> = Mutex::new(None);
+
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    let lockfile = match last.as_ref() {
+        Some((at, lockfile)) if at.elapsed() < REFRESH => lockfile.clone(),
+        _ => {
+            let lockfile = find_client(&system()).ok();
+            *last = Some((Instant::now(), lockfile.clone()));
+            lockfile
+        }
+    };
+    lockfile.ok_or_else(|| LcuError::NotRunning(format!("no {} process found", CLIENT_PROCESS)))
+}
+
+// Linux (Wine, Proton): every process has /proc/<pid>/cmdline with NUL separated
+// arguments. The root is configurable so a fake /proc can stand in for it.
+#[cfg(not(target_os = "windows"))]
+pub struct ProcFs {
+    root: PathBuf,
+}
+
+#[cfg(not(target_os = "windows"))]
+impl ProcFs {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl Default for ProcFs {
+    fn default() -> Self {
+        Self::new(Path::new("/proc"))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+impl ProcessSource for ProcFs {
+    fn processes(&self, name: &str) -> Vec<ClientProcess> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let mut processes = Vec::new();
+        for entry in entries.flatten() {
+            let pid = match entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) {
+                Some(pid) => pid,
+                None => continue,
+            };
+            // Processes can exit, or belong to another user, while we look
+            let cmdline = match fs::read(entry.path().join("cmdline")) {
+                Ok(cmdline) => cmdline,
+                Err(_) => continue,
+            };
+            let args: Vec<String> = cmdline.split(|b| *b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect();
+            if args.first().is_some_and(|exe| is_executable(exe, name)) {
+                processes.push(ClientProcess { pid, args });
+            }
+        }
+        processes.sort_by_key(|process| process.pid);
+        processes
+    }
+}
+
+// Windows: command lines aren't exposed without reading the other process' memory,
+// so ask the Win32_Process CIM class through powershell like the league detection does
+#[cfg(target_os = "windows")]
+pub struct WindowsProcesses;
+
+#[cfg(target_os = "windows")]
+impl ProcessSource for WindowsProcesses {
+    fn processes(&self, name: &str) -> Vec<ClientProcess> {
+        use std::os::windows::process::CommandExt;
+        use std::process::Command;
+
+        let script = format!(
+            r#"Get-CimInstance Win32_Process -Filter "Name='{}.exe'" | ForEach-Object {{ "$($_.ProcessId)`t$($_.CommandLine)" }}"#,
+            name
+        );
+        let output = match Command::new("powershell")
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW flag
+            .args(["-NoProfile", "-Command", &script])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(),
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (pid, command_line) = line.trim().split_once('\t')?;
+                Some(ClientProcess { pid: pid.parse().ok()?, args: split_command_line(command_line) })
+            })
+            .collect()
+    }
+}
+
+// Split a Windows command line on spaces outside of double quotes
+#[cfg(target_os = "windows")]
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ' ' | '\t' if !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(target_os = "windows")]
+pub fn system() -> WindowsProcesses {
+    WindowsProcesses
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn system() -> ProcFs {
+    ProcFs::default()
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    // A process in a fake /proc: <pid>/cmdline with NUL separated arguments
+    fn add_process(root: &Path, pid: u32, args: &[&str]) {
+        let dir = root.join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        let cmdline: Vec<u8> = args.iter().flat_map(|arg| arg.bytes().chain(Some(0))).collect();
+        fs::write(dir.join("cmdline"), cmdline).unwrap();
+    }
+
+    fn find(processes: &[(u32, &[&str])]) -> Result<Lockfile, LcuError> {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("self")).unwrap();
+        for (pid, args) in processes {
+            add_process(dir.path(), *pid, args);
+        }
+        find_client(&ProcFs::new(dir.path()))
+    }
+
+    #[test]
+    fn finds_client_with_exe() {
+        let lockfile = find(&[
+            (10, &["/usr/bin/wineserver"]),
+            (42, &[r"C:\Riot Games\League of Legends\LeagueClientUx.exe", "--app-port=54321", "--remoting-auth-token=hunter2"]),
+        ]).unwrap();
+        assert_eq!((lockfile.pid, lockfile.port, lockfile.password.as_str()), (42, 54321, "hunter2"));
+        assert_eq!(lockfile.protocol, "https");
+    }
+
+    #[test]
+    fn finds_client_without_exe() {
+        let lockfile = find(&[(7, &["/opt/league/LeagueClientUx", "--remoting-auth-token=abc", "--app-port=1234"])]).unwrap();
+        assert_eq!((lockfile.pid, lockfile.port, lockfile.password.as_str()), (7, 1234, "abc"));
+    }
+
+    #[test]
+    fn rejects_incomplete_command_lines() {
+        let missing_port = find(&[(1, &["LeagueClientUx.exe", "--remoting-auth-token=abc"])]);
+        assert!(matches!(missing_port, Err(LcuError::InvalidLockfile(_))));
+        let empty_token = find(&[(1, &["LeagueClientUx.exe", "--app-port=1234", "--remoting-auth-token="])]);
+        assert!(matches!(empty_token, Err(LcuError::InvalidLockfile(_))));
+        let bad_port = find(&[(1, &["LeagueClientUx.exe", "--app-port=http", "--remoting-auth-token=abc"])]);
+        assert!(matches!(bad_port, Err(LcuError::InvalidLockfile(_))));
+        assert!(matches!(find(&[]), Err(LcuError::NotRunning(_))));
+    }
+
+    #[test]
+    fn lowest_pid_wins() {
+        let lockfile = find(&[
+            (1000, &["LeagueClientUx.exe", "--app-port=1000", "--remoting-auth-token=newer"]),
+            (200, &["LeagueClientUx.exe", "--app-port=200", "--remoting-auth-token=older"]),
+            (300, &["LeagueClientUx.exe", "--app-port=300", "--remoting-auth-token=other"]),
+        ]).unwrap();
+        assert_eq!((lockfile.pid, lockfile.password.as_str()), (200, "older"));
+    }
+}
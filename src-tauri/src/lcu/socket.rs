@@ -0,0 +1,213 @@
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+use serde::Deserialize;
+use serde_json::Value;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::{Connector, MaybeTlsStream, Message, WebSocket};
+
+use crate::lcu::client::LcuClient;
+use crate::lcu::error::LcuError;
+use crate::lcu::gameflow::GameflowPhase;
+
+// Push events from the client's WAMP endpoint, served on the same port and auth as
+// the REST API. A subscription is `[5, "<topic>"]` and every change to a resource
+// arrives as `[8, "<topic>", { data, eventType, uri }]`.
+
+const GAMEFLOW_PHASE: &str = "/lol-gameflow/v1/gameflow-phase";
+const CHAMP_SELECT_SESSION: &str = "/lol-champ-select/v1/session";
+const LOBBY: &str = "/lol-lobby/v2/lobby";
+
+const SUBSCRIBE: u8 = 5;
+const EVENT: u8 = 8;
+
+// Changes that come in right after one another (champ select sends several per
+// second) are read together and handled once
+const BATCH_WINDOW: Duration = Duration::from_millis(250);
+
+// OnJsonApiEvent_lol-gameflow_v1_gameflow-phase for /lol-gameflow/v1/gameflow-phase
+fn topic(path: &str) -> String {
+    format!("OnJsonApiEvent{}", path.replace('/', "_"))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LcuEvent {
+    pub uri: String,
+    pub event_type: String, // Create, Update or Delete
+    pub data: Value,
+}
+
+impl LcuEvent {
+    // New phase when this is a gameflow-phase change
+    pub fn phase(&self) -> Option<GameflowPhase> {
+        if self.uri != GAMEFLOW_PHASE || self.event_type == "Delete" {
+            return None;
+        }
+        self.data.as_str().map(GameflowPhase::parse)
+    }
+}
+
+pub struct LcuSocket {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    stream: TcpStream, // Handle on the same socket for read timeouts
+    pid: u32,
+}
+
+impl LcuSocket {
+    // Connect to the client behind `client` and subscribe to gameflow, champ select and lobby changes
+    pub fn connect(client: &LcuClient) -> Result<Self, LcuError> {
+        let lockfile = client.lockfile();
+        let addr = SocketAddr::from(([127, 0, 0, 1], lockfile.port));
+        let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(3))?;
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+        let handle = stream.try_clone()?;
+
+        // Same self-signed certificate as the REST API
+        let (scheme, connector) = if lockfile.protocol == "https" {
+            let tls = native_tls::TlsConnector::builder()
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true)
+                .build()
+                .map_err(|e| LcuError::SocketError(e.to_string()))?;
+            ("wss", Connector::NativeTls(tls))
+        } else {
+            ("ws", Connector::Plain)
+        };
+
+        let mut request = format!("{}://{}/", scheme, addr).into_client_request()?;
+        let auth = client.auth().parse()
+            .map_err(|_| LcuError::SocketError("invalid auth header".into()))?;
+        request.headers_mut().insert("Authorization", auth);
+
+        let (mut socket, _) = tungstenite::client_tls_with_config(request, stream, None, Some(connector))
+            .map_err(|e| LcuError::SocketError(format!("handshake failed: {}", e)))?;
+
+        for path in [GAMEFLOW_PHASE, CHAMP_SELECT_SESSION, LOBBY] {
+            let subscribe = serde_json::json!([SUBSCRIBE, topic(path)]);
+            socket.send(Message::Text(subscribe.to_string()))?;
+        }
+
+        Ok(Self { socket, stream: handle, pid: lockfile.pid })
+    }
+
+    // Pid of the client process this socket belongs to
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    // Wait up to `timeout` for events. Returns the first one together with any that
+    // follow it closely, or nothing when the timeout passes quietly.
+    pub fn wait(&mut self, timeout: Duration) -> Result<Vec<LcuEvent>, LcuError> {
+        let mut events = Vec::new();
+        let mut deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(events);
+            }
+            match self.read(remaining)? {
+                Some(event) => {
+                    if events.is_empty() {
+                        deadline = Instant::now() + BATCH_WINDOW;
+                    }
+                    events.push(event);
+                }
+                None if events.is_empty() => continue,
+                None => return Ok(events),
+            }
+        }
+    }
+
+    // Next message within `timeout`; anything that isn't an event (pings, the
+    // subscription acknowledgements) reads as None
+    fn read(&mut self, timeout: Duration) -> Result<Option<LcuEvent>, LcuError> {
+        self.stream.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))?;
+        let text = match self.socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => return Err(LcuError::SocketError("closed by the client".into())),
+            Ok(_) => return Ok(None),
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(decode(&text))
+    }
+}
+
+// The payload of an `[8, topic, payload]` frame
+fn decode(text: &str) -> Option<LcuEvent> {
+    let message: Value = serde_json::from_str(text).ok()?;
+    match message.as_array().map(|m| m.as_slice()) {
+        Some([opcode, _topic, payload]) if opcode.as_u64() == Some(EVENT as u64) => {
+            serde_json::from_value(payload.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+impl Drop for LcuSocket {
+    fn drop(&mut self) {
+        let _ = self.socket.close(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topics_follow_the_wamp_naming() {
+        assert_eq!(topic(GAMEFLOW_PHASE), "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase");
+        assert_eq!(topic(CHAMP_SELECT_SESSION), "OnJsonApiEvent_lol-champ-select_v1_session");
+        assert_eq!(topic(LOBBY), "OnJsonApiEvent_lol-lobby_v2_lobby");
+    }
+
+    #[test]
+    fn decodes_event_frames_only() {
+        let frame = r#"[8, "OnJsonApiEvent_lol-gameflow_v1_gameflow-phase",
+            {"data": "ChampSelect", "eventType": "Update", "uri": "/lol-gameflow/v1/gameflow-phase"}]"#;
+        let event = decode(frame).unwrap();
+        assert_eq!(event.uri, GAMEFLOW_PHASE);
+        assert_eq!(event.event_type, "Update");
+        assert_eq!(event.data, Value::String("ChampSelect".into()));
+
+        // Fields the client leaves out fall back to their defaults
+        let frame = r#"[8, "OnJsonApiEvent", {"uri": "/lol-lobby/v2/lobby", "eventType": "Delete"}]"#;
+        let event = decode(frame).unwrap();
+        assert_eq!(event.uri, LOBBY);
+        assert_eq!(event.data, Value::Null);
+
+        // Subscription acks, other opcodes and garbage are not events
+        assert!(decode(r#"[5, "OnJsonApiEvent_lol-lobby_v2_lobby"]"#).is_none());
+        assert!(decode(r#"[0, "session", 1, "RiotRemotingServer"]"#).is_none());
+        assert!(decode(r#"[8, "OnJsonApiEvent", "not an object"]"#).is_none());
+        assert!(decode(r#"{"opcode": 8}"#).is_none());
+        assert!(decode("").is_none());
+    }
+
+    #[test]
+    fn only_gameflow_updates_carry_a_phase() {
+        let event = |uri: &str, event_type: &str, data: Value| LcuEvent {
+            uri: uri.into(),
+            event_type: event_type.into(),
+            data,
+        };
+        let in_progress = Value::String("InProgress".into());
+        let expected = Some(GameflowPhase::InProgress);
+
+        assert_eq!(event(GAMEFLOW_PHASE, "Update", in_progress.clone()).phase(), expected);
+        assert_eq!(event(GAMEFLOW_PHASE, "Create", in_progress.clone()).phase(), expected);
+        assert_eq!(
+            event(GAMEFLOW_PHASE, "Update", Value::String("SomethingNew".into())).phase(),
+            Some(GameflowPhase::Unknown)
+        );
+        // The resource going away is not a phase change, nor is anything else
+        assert_eq!(event(GAMEFLOW_PHASE, "Delete", in_progress.clone()).phase(), None);
+        assert_eq!(event(GAMEFLOW_PHASE, "Update", Value::Null).phase(), None);
+        assert_eq!(event(LOBBY, "Update", in_progress.clone()).phase(), None);
+        assert_eq!(event(CHAMP_SELECT_SESSION, "Update", in_progress).phase(), None);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::lcu::client::LcuClient;
+use crate::lcu::gameflow::{GameflowPhase, GameflowTracker, QueueKind, Step};
+
+// The detection half of the LCU watcher: read the client's state once per cycle
+// and run it through the gameflow tracker. Carrying out the actions (injecting,
+// cleaning up) is left to the caller.
+
+#[derive(Debug, Default)]
+pub struct GameflowWatcher {
+    tracker: GameflowTracker,
+    // Local player's puuid, looked up once per client process
+    local_puuid: Option<(u32, String)>,
+}
+
+// What one cycle saw and what the tracker made of it
+#[derive(Debug, Clone)]
+pub struct WatcherCycle {
+    pub previous: GameflowPhase,
+    pub phase: GameflowPhase,
+    pub queue: QueueKind,
+    pub step: Step,
+}
+
+impl GameflowWatcher {
+    // `phase` comes from a push event, otherwise it is fetched
+    pub fn cycle(&mut self, client: &LcuClient, phase: Option<GameflowPhase>) -> WatcherCycle {
+        let pid = client.lockfile().pid;
+        if self.local_puuid.as_ref().map(|(p, _)| *p) != Some(pid) {
+            self.local_puuid = client.current_summoner().ok()
+                .filter(|summoner| !summoner.puuid.is_empty())
+                .map(|summoner| (pid, summoner.puuid));
+        }
+        let puuid = self.local_puuid.as_ref().map(|(_, puuid)| puuid.as_str());
+
+        // Phase tracking: get current phase from /lol-gameflow/v1/gameflow-phase
+        let phase = match phase.map_or_else(|| client.gameflow_phase(), Ok) {
+            Ok(phase) => phase,
+            Err(e) => {
+                println!("[LCU API Debug] Failed to fetch gameflow-phase: {}", e);
+                GameflowPhase::None
+            }
+        };
+        let previous = self.tracker.phase();
+
+        let snapshot = client.snapshot(phase);
+        let step = self.tracker.step(&snapshot, puuid);
+        WatcherCycle { previous, phase, queue: snapshot.queue(), step }
+    }
+}
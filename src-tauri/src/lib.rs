@@ -1,6 +1,10 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 use std::time::{SystemTime, UNIX_EPOCH};
 
+// Injection and the League client API live in the library so integration tests can reach them
+pub mod injection;
+pub mod lcu;
+
 #[tauri::command]
 fn greet() -> String {
   let now = SystemTime::now();
@@ -2,7 +2,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod commands;
-mod injection;
+
+use osskins_lib::{injection, lcu};
 
 use commands::*;
 use tauri::{Manager};
@@ -79,6 +80,10 @@ fn main() {
             save_fantome_file,
             select_league_directory,
             inject_skins,
+            plan_injection,
+            cancel_injection,
+            get_injection_state,
+            inspect_mod,
             inject_game_skins,
             save_league_path,
             load_league_path,
@@ -91,9 +96,11 @@ fn main() {
             // GitHub update commands
             check_github_updates,
             update_champion_data_from_github,
+            update_wad_hashes,
             
             // custom skin commands
             upload_custom_skin,
+            import_custom_skins,
             get_custom_skins,
             delete_custom_skin,
             
@@ -0,0 +1,147 @@
+// End-to-end runs of SkinInjector::inject_skins against a fake League folder and a fake mod-tools
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use osskins_lib::injection::fixtures::{read_wad_file, write_fantome, FakeLeague};
+use osskins_lib::injection::{FakeModTools, InjectionError, Skin, SkinInjector};
+
+const AHRI_WAD: &str = "DATA/FINAL/Champions/Ahri.wad.client";
+const TEXTURE: &str = "assets/characters/ahri/skins/base/ahri_base_tx_cm.dds";
+
+// The running patcher is global, injections from parallel tests would stop each other's
+static SERIAL: Mutex<()> = Mutex::new(());
+
+struct Setup {
+    _dir: tempfile::TempDir,
+    league: FakeLeague,
+    app_dir: PathBuf,
+    fake: FakeModTools,
+    injector: SkinInjector,
+}
+
+impl Setup {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let league = FakeLeague::create(&dir.path().join("League of Legends")).unwrap();
+        league.add_champion_wad("Ahri", &[(TEXTURE, b"base texture")]).unwrap();
+        let app_dir = dir.path().join("app");
+        let fake = FakeModTools::new();
+        let mut injector = SkinInjector::headless(league.root_path(), &app_dir, Arc::new(fake.clone())).unwrap();
+        injector.initialize().unwrap();
+        Self { _dir: dir, league, app_dir, fake, injector }
+    }
+
+    // A fantome that replaces Ahri's texture, picked by path like a custom skin
+    fn skin(&self, name: &str, texture: &[u8]) -> Skin {
+        let path = self.app_dir.join("downloads").join(format!("{}.fantome", name));
+        write_fantome(&path, "Ahri", &[(TEXTURE, texture)]).unwrap();
+        Skin { champion_id: 103, skin_id: 1, chroma_id: None, fantome_path: Some(path.to_string_lossy().to_string()) }
+    }
+
+    fn inject(&mut self, skins: &[Skin]) -> Result<(), InjectionError> {
+        let champions_dir = self.app_dir.join("champions");
+        self.injector.inject_skins(skins, &champions_dir)
+    }
+
+    // Overlay folders runoverlay was started with, in order
+    fn overlays(&self) -> Vec<PathBuf> {
+        self.fake.calls().iter()
+            .filter_map(|call| call.strip_prefix("runoverlay "))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    fn installed_mods(&self) -> Vec<String> {
+        let mut mods: Vec<String> = fs::read_dir(self.league.game_path().join("mods"))
+            .map(|entries| entries.filter_map(Result::ok).map(|e| e.file_name().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+        mods.sort();
+        mods
+    }
+}
+
+impl Drop for Setup {
+    fn drop(&mut self) {
+        let _ = self.injector.cleanup();
+    }
+}
+
+fn overlay_texture(overlay_dir: &Path) -> Option<Vec<u8>> {
+    read_wad_file(&overlay_dir.join(AHRI_WAD), TEXTURE).unwrap()
+}
+
+#[test]
+fn fresh_build() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mut setup = Setup::new();
+    let skin = setup.skin("Star Guardian Ahri", b"star guardian texture");
+
+    setup.inject(&[skin]).unwrap();
+
+    let overlays = setup.overlays();
+    assert_eq!(overlays.len(), 1);
+    assert!(overlays[0].starts_with(setup.app_dir.join("overlay_cache")));
+    assert_eq!(overlay_texture(&overlays[0]).as_deref(), Some(&b"star guardian texture"[..]));
+    assert_eq!(setup.installed_mods(), ["Star Guardian Ahri"]);
+    let game_cfg = fs::read_to_string(setup.league.game_path().join("Game.cfg")).unwrap();
+    assert!(game_cfg.contains("EnableMods=1"));
+}
+
+#[test]
+fn cache_hit() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mut setup = Setup::new();
+    let skin = setup.skin("Arcade Ahri", b"arcade texture");
+    setup.inject(std::slice::from_ref(&skin)).unwrap();
+    let champions_dir = setup.app_dir.join("champions");
+    assert!(setup.injector.plan_injection(std::slice::from_ref(&skin), &champions_dir).unwrap().cached);
+
+    // A rebuild would stage Game/mods again and drop this file
+    let marker = setup.league.game_path().join("mods").join("Arcade Ahri").join("marker");
+    fs::write(&marker, b"untouched").unwrap();
+    setup.inject(&[skin]).unwrap();
+
+    let overlays = setup.overlays();
+    assert_eq!(overlays.len(), 2);
+    assert_eq!(overlays[0], overlays[1]);
+    assert!(marker.exists());
+    assert_eq!(overlay_texture(&overlays[1]).as_deref(), Some(&b"arcade texture"[..]));
+}
+
+#[test]
+fn validation_failure() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mut setup = Setup::new();
+    let good = setup.skin("Arcade Ahri", b"arcade texture");
+    setup.inject(std::slice::from_ref(&good)).unwrap();
+
+    let broken = setup.app_dir.join("downloads").join("Broken.fantome");
+    fs::write(&broken, b"not a zip archive").unwrap();
+    let bad = Skin { fantome_path: Some(broken.to_string_lossy().to_string()), ..good };
+
+    let err = setup.inject(&[bad]).unwrap_err();
+    assert!(err.to_string().contains("Not a valid zip archive"), "{}", err);
+    // Nothing was extracted or started, the previous loadout is still in place
+    assert_eq!(setup.overlays().len(), 1);
+    assert_eq!(setup.installed_mods(), ["Arcade Ahri"]);
+    assert!(!setup.app_dir.join("mods").join("Broken").exists());
+}
+
+#[test]
+fn rollback_on_patcher_failure() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let mut setup = Setup::new();
+    let first = setup.skin("Arcade Ahri", b"arcade texture");
+    setup.inject(&[first]).unwrap();
+
+    setup.injector.set_backend(Arc::new(FakeModTools::new().unavailable()));
+    let second = setup.skin("Star Guardian Ahri", b"star guardian texture");
+    let err = setup.inject(&[second]).unwrap_err();
+    assert!(err.to_string().contains("unavailable"), "{}", err);
+
+    assert_eq!(setup.installed_mods(), ["Arcade Ahri"]);
+    let game_path = setup.league.game_path();
+    assert!(!game_path.join("mods.staging").exists());
+    assert!(!game_path.join("mods.backup").exists());
+}
@@ -0,0 +1,171 @@
+// The LCU watcher's detection run against MockLcu, found through its lockfile like
+// start_lcu_watcher finds the real client, over one recorded session per game mode
+use osskins_lib::injection::{skins_for_champions, Skin};
+use osskins_lib::lcu::fixtures::{Recording, CHAT};
+use osskins_lib::lcu::mock::MockLcu;
+use osskins_lib::lcu::{Action, GameflowPhase, GameflowWatcher, LcuClient};
+
+use GameflowPhase::{ChampSelect, EndOfGame, GameStart, Matchmaking, PreEndOfGame, TerminatedInError, WaitingForStats};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Event {
+    Inject(Vec<i64>),
+    Reinject(Vec<i64>),
+    Cleanup,
+}
+
+// Play the recording whose name starts with `mode`, one watcher cycle per step.
+// Returns what the watcher would have done, with the step and phase it happened in.
+fn watch(mode: &str) -> Vec<(usize, GameflowPhase, Event)> {
+    let recording = Recording::all().unwrap()
+        .into_iter()
+        .find(|recording| recording.name.starts_with(mode))
+        .unwrap();
+    let steps = recording.steps.len();
+
+    let mock = MockLcu::start().unwrap();
+    let league_dir = tempfile::tempdir().unwrap();
+    mock.write_lockfile(league_dir.path()).unwrap();
+    mock.play(recording);
+
+    let league_path = league_dir.path().to_string_lossy().to_string();
+    let mut watcher = GameflowWatcher::default();
+    let mut events = Vec::new();
+    for index in 0..steps {
+        // The watcher reconnects every cycle
+        let client = LcuClient::connect(&league_path).unwrap();
+        assert_eq!(client.lockfile().port, mock.port());
+
+        let cycle = watcher.cycle(&client, None);
+        let champion_ids = &cycle.step.champion_ids;
+        for action in &cycle.step.actions {
+            match action {
+                Action::Inject => events.push((index, cycle.phase, Event::Inject(champion_ids.clone()))),
+                Action::Reinject => events.push((index, cycle.phase, Event::Reinject(champion_ids.clone()))),
+                Action::Cleanup => events.push((index, cycle.phase, Event::Cleanup)),
+                Action::Prepare | Action::Nothing => {}
+            }
+        }
+        mock.advance();
+    }
+    events
+}
+
+#[test]
+fn draft() {
+    // Ahri once she is locked, Lux after the trade, cleanup once the game is over
+    assert_eq!(watch("Ranked draft"), [
+        (4, ChampSelect, Event::Inject(vec![103])),
+        (5, ChampSelect, Event::Reinject(vec![99])),
+        (8, WaitingForStats, Event::Cleanup),
+    ]);
+}
+
+#[test]
+fn aram() {
+    // The random champion can still be swapped, so nothing happens before the game starts
+    assert_eq!(watch("ARAM"), [
+        (3, GameStart, Event::Inject(vec![236])),
+        (5, TerminatedInError, Event::Cleanup),
+    ]);
+}
+
+#[test]
+fn swift_play() {
+    // Both lobby picks on queueing, again after the declined ready check changed a slot
+    assert_eq!(watch("Swift Play"), [
+        (1, Matchmaking, Event::Inject(vec![1, 22])),
+        (4, Matchmaking, Event::Inject(vec![1, 51])),
+        (7, PreEndOfGame, Event::Cleanup),
+    ]);
+}
+
+#[test]
+fn brawl() {
+    assert_eq!(watch("Brawl"), [
+        (1, Matchmaking, Event::Inject(vec![5])),
+        (5, EndOfGame, Event::Cleanup),
+    ]);
+}
+
+fn skin(champion_id: u32, skin_id: u32, chroma_id: Option<u32>) -> Skin {
+    Skin { champion_id, skin_id, chroma_id, fantome_path: None }
+}
+
+// What the injector is handed for each inject, resolved like the auto-inject flow
+// resolves the saved selections
+fn injected_skins(mode: &str, saved: &[Skin]) -> Vec<(usize, Action, Vec<Skin>)> {
+    watch(mode).into_iter()
+        .filter_map(|(index, _, event)| match event {
+            Event::Inject(ids) => Some((index, Action::Inject, skins_for_champions(saved, &ids))),
+            Event::Reinject(ids) => Some((index, Action::Reinject, skins_for_champions(saved, &ids))),
+            Event::Cleanup => None,
+        })
+        .collect()
+}
+
+#[test]
+fn injects_the_saved_skins() {
+    let ahri = skin(103, 103015, None);
+    let lux = skin(99, 99007, Some(99008));
+    let annie = skin(1, 1005, None);
+    let caitlyn = skin(51, 51022, None);
+    let saved = [ahri.clone(), lux.clone(), annie.clone(), caitlyn.clone(), skin(236, 236001, None)];
+
+    // The trade replaces Ahri's skin with Lux's
+    assert_eq!(injected_skins("Ranked draft", &saved), [
+        (4, Action::Inject, vec![ahri]),
+        (5, Action::Reinject, vec![lux]),
+    ]);
+
+    // Nothing is saved for Ashe (22), her slot goes in without a skin
+    assert_eq!(injected_skins("Swift Play", &saved), [
+        (1, Action::Inject, vec![annie.clone()]),
+        (4, Action::Inject, vec![annie, caitlyn]),
+    ]);
+}
+
+#[test]
+fn recordings_replay_over_https() {
+    let mock = MockLcu::start().unwrap();
+    for recording in Recording::all().unwrap() {
+        let name = recording.name.clone();
+        let mismatches = mock.replay(recording).unwrap();
+        assert!(mismatches.is_empty(), "{}: {:#?}", name, mismatches);
+    }
+}
+
+#[test]
+fn chat() {
+    let mock = MockLcu::start().unwrap();
+    mock.load_chat(CHAT).unwrap();
+    let client = mock.client();
+
+    let friends = client.friends().unwrap();
+    let names: Vec<&str> = friends.iter().map(|friend| friend.name.as_str()).collect();
+    assert_eq!(names, ["Duo Partner", "Away Friend", ""]);
+
+    // Conversation ids are both puuids, sorted and joined with an underscore
+    let conversation = "friend-puuid_local-puuid";
+    let history = client.messages(conversation).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].body, "osskins sync please");
+
+    client.send_message(conversation, "synced").unwrap();
+    let messages = mock.messages(conversation);
+    assert_eq!(messages.len(), 2);
+    assert_eq!((messages[1].body.as_str(), messages[1].from_id.as_str()), ("synced", "local-puuid"));
+    assert_eq!(client.messages(conversation).unwrap().len(), 2);
+
+    // A conversation that was never started has no messages rather than failing
+    assert!(client.messages("away-puuid_local-puuid").unwrap().is_empty());
+
+    let path = format!("/lol-chat/v1/conversations/{}/messages", conversation);
+    assert_eq!(mock.requests(), [
+        "GET /lol-chat/v1/friends".to_string(),
+        format!("GET {}", path),
+        format!("POST {}", path),
+        format!("GET {}", path),
+        "GET /lol-chat/v1/conversations/away-puuid_local-puuid/messages".to_string(),
+    ]);
+}
@@ -30,8 +30,10 @@ export const CustomSkinCard = React.memo(function CustomSkinCard({
   const clearSelection = useGameStore((s) => s.clearSelection);
 
   // Check if this skin is selected
+  // Selections saved before custom skins had ids only carry the file path
+  const selected = selectedSkins.get(skin.champion_id);
   const isSelected =
-    selectedSkins.get(skin.champion_id)?.fantome === skin.file_path;
+    selected?.customSkinId === skin.id || selected?.fantome === skin.file_path;
 
   // Generate a fake skin ID for custom skins (used for selection tracking)
   const fakeSkinId = parseInt(skin.id.replace(/\D/g, "").slice(0, 8)) || 999999;
@@ -78,8 +80,8 @@ export const CustomSkinCard = React.memo(function CustomSkinCard({
     if (isSelected) {
       clearSelection(skin.champion_id);
     } else {
-      // For custom skins we use the file path directly
-      selectSkin(skin.champion_id, fakeSkinId, undefined, skin.file_path);
+      // Custom skins are saved by id, the backend looks up where the file lives
+      selectSkin(skin.champion_id, fakeSkinId, undefined, skin.file_path, skin.id);
     }
   };
 
@@ -5,6 +5,7 @@ import { cn } from "@/lib/utils";
 import { Button } from "./ui/button";
 import { CustomSkin } from "@/lib/types";
 import { shallow } from "zustand/shallow";
+import { convertFileSrc } from "@tauri-apps/api/core";
 
 import { Trash2, Play, Check } from "lucide-react";
 import { useGameStore } from "@/lib/store";
@@ -24,8 +25,10 @@ export function CustomSkinItem({ skin, onDelete }: CustomSkinCardProps) {
   const clearSelection = useGameStore((s) => s.clearSelection);
 
   // Check if this skin is selected
+  // Selections saved before custom skins had ids only carry the file path
+  const selected = selectedSkins.get(skin.champion_id);
   const isSelected =
-    selectedSkins.get(skin.champion_id)?.fantome === skin.file_path;
+    selected?.customSkinId === skin.id || selected?.fantome === skin.file_path;
 
   // Generate a fake skin ID for custom skins (used for selection tracking)
   const fakeSkinId = parseInt(skin.id.replace(/\D/g, "").slice(0, 8)) || 999999;
@@ -72,8 +75,8 @@ export function CustomSkinItem({ skin, onDelete }: CustomSkinCardProps) {
     if (isSelected) {
       clearSelection(skin.champion_id);
     } else {
-      // For custom skins we use the file path directly
-      selectSkin(skin.champion_id, fakeSkinId, undefined, skin.file_path);
+      // Custom skins are saved by id, the backend looks up where the file lives
+      selectSkin(skin.champion_id, fakeSkinId, undefined, skin.file_path, skin.id);
     }
   };
 
@@ -87,9 +90,17 @@ export function CustomSkinItem({ skin, onDelete }: CustomSkinCardProps) {
       onMouseEnter={handleMouseEnter}
       onMouseLeave={handleMouseLeave}
     >
-      <div className="p-2">
-        {!isSelected && <Play className="size-8" />}
-        {isSelected && <Check className="size-8 text-primary" />}
+      <div className="relative m-1 flex h-12 w-20 shrink-0 items-center justify-center overflow-hidden rounded-md">
+        {skin.preview_image && (
+          // eslint-disable-next-line @next/next/no-img-element
+          <img
+            src={convertFileSrc(skin.preview_image)}
+            alt=""
+            className="absolute inset-0 h-full w-full object-cover opacity-70"
+          />
+        )}
+        {!isSelected && <Play className="relative size-8" />}
+        {isSelected && <Check className="relative size-8 text-primary" />}
       </div>
 
       <div className="flex justify-between gap-2 px-2 items-center w-full">
@@ -18,6 +18,7 @@ export function useChampionPersistence() {
       skin_id: s.skinId,
       chroma_id: s.chromaId,
       fantome: s.fantome,
+      custom_skin_id: s.customSkinId,
     }));
 
     invoke("save_selected_skins", {
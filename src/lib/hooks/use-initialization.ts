@@ -39,12 +39,14 @@ export function useInitialization() {
                 skin_id: number;
                 chroma_id?: number;
                 fantome?: string;
+                custom_skin_id?: string;
               };
               selectSkin(
                 skinObj.champion_id,
                 skinObj.skin_id,
                 skinObj.chroma_id,
-                skinObj.fantome
+                skinObj.fantome,
+                skinObj.custom_skin_id
               );
             }
           });
@@ -7,6 +7,7 @@ interface SelectedSkin {
   skinId: number;
   chromaId?: number;
   fantome?: string;
+  customSkinId?: string; // Set when the selection is an uploaded custom skin
   timestamp?: number; // For tracking usage
 }
 
@@ -92,7 +93,8 @@ interface GameState {
     championId: number,
     skinId: number,
     chromaId?: number,
-    fantome?: string
+    fantome?: string,
+    customSkinId?: string
   ) => void;
   clearSelection: (championId: number) => void;
   clearAllSelections: () => void;
@@ -249,7 +251,7 @@ export const useGameStore = create<GameState>()(
       setInjectionStatus: (status) => set({ injectionStatus: status }),
 
       // Enhanced skin management
-      selectSkin: (championId, skinId, chromaId, fantome) => {
+      selectSkin: (championId, skinId, chromaId, fantome, customSkinId) => {
         set((state) => {
           const newSelectedSkins = new Map(state.selectedSkins);
           newSelectedSkins.set(championId, {
@@ -257,6 +259,7 @@ export const useGameStore = create<GameState>()(
             skinId,
             chromaId,
             fantome,
+            customSkinId,
             timestamp: Date.now(),
           });
           